@@ -1,3 +1,9 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread::Thread;
+
+use crossbeam_queue::SegQueue;
 use divan::{bench as benchmark, main as run_benchmarks};
 use futures::{future, stream, StreamExt};
 
@@ -92,3 +98,137 @@ mod one_hundred_thousand {
         assert_eq!(n, N);
     }
 }
+
+mod contended {
+    #[allow(unused_imports)]
+    use super::*;
+
+    /// One hundred thousand sends, shared between `producers` threads.
+    #[benchmark(min_time = 1, args = [1, 2, 4, 8, 16])]
+    fn external_sends(producers: usize) {
+        let store = Store::with_initial(State(0));
+        std::thread::scope(|scope| {
+            for _ in 0..producers {
+                scope.spawn(|| {
+                    for _ in 0..N / producers {
+                        store.send(std::hint::black_box(Action::A));
+                    }
+                });
+            }
+        });
+
+        let n = store.into_inner();
+        assert_eq!(n, N / producers * producers);
+    }
+
+    /// The same sends through the lock-free queue behind a `Store`’s channel; with a
+    /// consumer thread draining it, as a `Store`’s runtime does.
+    ///
+    /// Only the channel is measured, as the consumer has no `Reducer` to call; to compare
+    /// like-for-like with `external_sends_locked`.
+    #[benchmark(min_time = 1, args = [1, 2, 4, 8, 16])]
+    fn external_sends_lock_free(producers: usize) {
+        let queue = SegQueue::new();
+        let parked = AtomicBool::new(false); // the consumer; on an empty queue
+
+        let expected = N / producers * producers;
+        std::thread::scope(|scope| {
+            let consumer = scope.spawn(|| {
+                let mut n = 0;
+
+                while n < expected {
+                    match queue.pop() {
+                        Some(action) => {
+                            std::hint::black_box(action);
+                            n += 1;
+                        }
+                        None => {
+                            parked.store(true, Ordering::SeqCst);
+                            if queue.is_empty() {
+                                std::thread::park();
+                            }
+
+                            parked.store(false, Ordering::SeqCst);
+                        }
+                    }
+                }
+
+                n
+            });
+
+            for _ in 0..producers {
+                let (queue, parked, waiting) = (&queue, &parked, consumer.thread().clone());
+                scope.spawn(move || {
+                    for _ in 0..N / producers {
+                        queue.push(std::hint::black_box(Action::A));
+
+                        if parked.swap(false, Ordering::SeqCst) {
+                            waiting.unpark();
+                        }
+                    }
+                });
+            }
+
+            assert_eq!(consumer.join().unwrap(), expected);
+        });
+    }
+
+    /// The same sends through the `Mutex<VecDeque>` channel that `Store`s used before it
+    /// was made lock-free; as a baseline for `external_sends_lock_free`.
+    #[benchmark(min_time = 1, args = [1, 2, 4, 8, 16])]
+    fn external_sends_locked(producers: usize) {
+        struct Shared {
+            queue: VecDeque<Action>,
+            waiting: Option<Thread>, // the consumer; if it is parked on an empty queue
+        }
+
+        let shared = Mutex::new(Shared {
+            queue: VecDeque::new(),
+            waiting: None,
+        });
+
+        let expected = N / producers * producers;
+        std::thread::scope(|scope| {
+            let consumer = scope.spawn(|| {
+                let mut buffer = VecDeque::new();
+                let mut n = 0;
+
+                while n < expected {
+                    let mut locked = shared.lock().unwrap();
+                    if locked.queue.is_empty() {
+                        locked.waiting = Some(std::thread::current());
+                        drop(locked);
+
+                        std::thread::park();
+                        continue;
+                    }
+
+                    // take every queued action at once; as the baseline’s `Receiver` did
+                    std::mem::swap(&mut locked.queue, &mut buffer);
+                    drop(locked);
+
+                    n += buffer.drain(..).map(std::hint::black_box).count();
+                }
+
+                n
+            });
+
+            for _ in 0..producers {
+                scope.spawn(|| {
+                    for _ in 0..N / producers {
+                        let mut locked = shared.lock().unwrap();
+                        let waiting = locked.waiting.take(); // there are no “extra” wakes
+                        locked.queue.push_back(std::hint::black_box(Action::A));
+                        drop(locked);
+
+                        if let Some(consumer) = waiting {
+                            consumer.unpark(); // _after_ the lock has been released
+                        }
+                    }
+                });
+            }
+
+            assert_eq!(consumer.join().unwrap(), expected);
+        });
+    }
+}
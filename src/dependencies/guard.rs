@@ -44,6 +44,50 @@ impl<T: 'static> Drop for Guard<T> {
     }
 }
 
+/// Dependency values that can be made current, again and again, with [`enter`].
+///
+/// [`enter`]: Registered::enter
+#[derive(Clone, Default)]
+pub struct Registered {
+    values: Vec<(TypeId, Rc<dyn Any + 'static>)>,
+}
+
+impl Registered {
+    /// Adds `value`; shadowing any earlier value of the same type.
+    pub(crate) fn insert<T: 'static>(&mut self, value: T) {
+        self.values.push((TypeId::of::<T>(), Rc::new(value)));
+    }
+
+    /// Makes all of the values current until the returned `Entered` is dropped.
+    pub(crate) fn enter(&self) -> Entered {
+        PER_THREAD.with_borrow_mut(|map| {
+            for (type_id, value) in &self.values {
+                map.entry(*type_id).or_default().push(value.clone());
+            }
+        });
+
+        Entered {
+            types: self.values.iter().map(|(type_id, _)| *type_id).collect(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+pub struct Entered {
+    types: Vec<TypeId>,
+    _marker: PhantomData<*const ()>, // !Send
+}
+
+impl Drop for Entered {
+    fn drop(&mut self) {
+        PER_THREAD.with_borrow_mut(|map| {
+            for type_id in self.types.iter().rev() {
+                map.get_mut(type_id).and_then(|vec| vec.pop());
+            }
+        });
+    }
+}
+
 /// `TypeId`s are already hashed.
 pub type UnhashMap<K, V> = HashMap<K, V, BuildHasherDefault<Unhasher>>;
 use std::hash::{BuildHasherDefault, Hasher};
@@ -31,6 +31,9 @@ pub trait Tuple {
 
     #[doc(hidden)]
     fn guards(self) -> Self::Output;
+
+    #[doc(hidden)]
+    fn register(self, registered: &mut guard::Registered);
 }
 
 macro_rules! tuple_impl {
@@ -45,6 +48,11 @@ macro_rules! tuple_impl {
                 let ( $($val,)+ ) = self;
                 ( $(guard::Guard::new($val),)+ )
             }
+
+            fn register(self, registered: &mut guard::Registered) {
+                let ( $($val,)+ ) = self;
+                $(registered.insert($val);)+
+            }
         }
     };
 }
@@ -287,7 +287,8 @@ impl<T: DependencyDefault> Dependency<T> {
                 let detailed_explanation = r#".
 
 DependencyDefault types are not allowed to use their default implementation within units tests.
-Either register the dependency on the TestStore or use with_dependency(…) within the test itself.
+Either register the dependency on the TestStore, with TestStore::with_dependency(…), or use with_dependency(…)
+within the test itself.
 "#;
                 panic!(
                     "Dependency<{0}> was constructed during a test,\nbut {0} was not registered{1}",
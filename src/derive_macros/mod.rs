@@ -57,6 +57,7 @@
 //!     #[derive(Default)]
 //!     pub struct State { /* … */ }
 //!
+//! #   #[derive(Debug)]
 //!     #[derive(Clone)] // ⒈
 //!     pub enum Action { /* … */ }
 //!
@@ -75,6 +76,7 @@
 //!     #[derive(Default)]
 //!     pub struct State;
 //!
+//! #   #[derive(Debug)]
 //!     #[derive(Clone)] // ⒈
 //!     pub enum Action { /* … */ }
 //!
@@ -98,6 +100,7 @@
 //! #   c: Vec<u32>,
 //! }
 //!
+//! # #[derive(Debug)]
 //! #[derive(Clone, From, TryInto)] // ⒊
 //! enum Action {
 //!     SomeAction, // parent actions
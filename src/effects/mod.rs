@@ -10,10 +10,14 @@ use std::time::{Duration, Instant};
 use futures::stream::{iter, once};
 use futures::{Future, Stream, StreamExt};
 
+use crate::dependencies::{with_dependency, Dependency};
+use crate::Priority;
+
 pub(crate) use delay::Delay;
-pub(crate) use task::Executor;
+pub use scheduler::Clock;
 #[doc(hidden)]
 pub use task::Task;
+pub(crate) use task::{notifying, Executor, Tasks};
 
 mod delay;
 pub(crate) mod scheduler;
@@ -50,7 +54,7 @@ pub trait Effects: Clone + Scheduler<Action = <Self as Effects>::Action> {
         <Self as Effects>::Action: 'static,
     {
         let stream = once(future).filter_map(|action| async move { action });
-        self.task(stream).detach()
+        self.task(stream).forget()
     }
 
     /// An effect that runs a [`Stream`](https://docs.rs/futures/latest/futures/stream/index.html)
@@ -58,7 +62,46 @@ pub trait Effects: Clone + Scheduler<Action = <Self as Effects>::Action> {
     /// [`Reducer`][`crate::Reducer`].
     #[inline(always)]
     fn stream<S: Stream<Item = <Self as Effects>::Action> + 'static>(&self, stream: S) {
-        self.task(stream).detach()
+        self.task(stream).forget()
+    }
+
+    /// An `Effects` whose [`Task`]s send their actions with `priority`.
+    ///
+    /// Actions sent directly, with [`action`][Effects::action], are always reduced
+    /// immediately after the current one; regardless of priority.
+    ///
+    /// ```rust
+    /// # use composable::*;
+    /// # use composable::effects::Effects as _;
+    /// # use futures::stream;
+    /// # #[derive(Default)]
+    /// # struct State { progress: Vec<u8> }
+    /// #
+    /// # #[derive(Clone, Debug, PartialEq)]
+    /// # enum Action { Download, Progress(u8) }
+    /// #
+    /// # impl Reducer for State {
+    /// #     type Action = Action;
+    /// #     type Output = Self;
+    /// #
+    /// #     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+    /// #         match action {
+    /// #             Action::Download => {
+    /// #                 let progress_updates = stream::iter([50, 100].map(Action::Progress));
+    /// send.with_priority(Priority::Low).stream(progress_updates);
+    /// #             }
+    /// #             Action::Progress(percent) => self.progress.push(percent),
+    /// #         }
+    /// #     }
+    /// # }
+    /// #
+    /// # let store = Store::<State>::default();
+    /// # store.send(Action::Download);
+    /// # assert_eq!(store.into_inner().progress, [50, 100]);
+    /// ```
+    #[inline(always)]
+    fn with_priority(&self, priority: Priority) -> Prioritized<Self> {
+        Prioritized(self.clone(), priority)
     }
 
     /// Scopes the `Effects` down to one that sends child actions.
@@ -90,7 +133,8 @@ pub trait Scheduler {
 
     #[doc(hidden)]
     fn now(&self) -> Instant {
-        Instant::now()
+        let clock = Dependency::<Clock>::new().map(Clock::now);
+        clock.unwrap_or_else(Instant::now)
     }
 
     #[doc(hidden)]
@@ -266,6 +310,11 @@ where
 {
     type Action = Child;
 
+    #[inline(always)]
+    fn now(&self) -> Instant {
+        self.0.now()
+    }
+
     #[inline(always)]
     fn schedule(
         &self,
@@ -279,6 +328,51 @@ where
     }
 }
 
+/// An `Effects` whose [`Task`]s send their actions with a [`Priority`].
+///
+/// This `struct` is created by the [`with_priority`] method on [`Effects`]. See its
+/// documentation for more.
+///
+/// [`with_priority`]: Effects::with_priority
+#[derive(Clone)]
+pub struct Prioritized<E>(E, Priority);
+
+impl<E: Effects> Effects for Prioritized<E> {
+    type Action = <E as Effects>::Action;
+
+    #[inline(always)]
+    fn action(&self, action: impl Into<<Self as Effects>::Action>) {
+        self.0.action(action);
+    }
+
+    #[inline(always)]
+    fn task<S: Stream<Item = <Self as Effects>::Action> + 'static>(&self, stream: S) -> Task {
+        with_dependency(self.1, || self.0.task(stream)) // read by `Task::new`
+    }
+}
+
+#[doc(hidden)]
+impl<E: Effects> Scheduler for Prioritized<E> {
+    type Action = <E as Effects>::Action;
+
+    #[inline(always)]
+    fn now(&self) -> Instant {
+        self.0.now()
+    }
+
+    #[inline(always)]
+    fn schedule(
+        &self,
+        action: Self::Action,
+        after: impl IntoIterator<Item = Delay> + 'static,
+    ) -> Task
+    where
+        Self::Action: Clone + 'static,
+    {
+        with_dependency(self.1, || self.0.schedule(action, after))
+    }
+}
+
 #[doc(hidden)]
 // `Parent` for `Effects::scope` tuples
 impl<Action: 'static> Effects for Weak<RefCell<VecDeque<Action>>> {
@@ -1,9 +1,9 @@
 use std::cmp::Reverse;
 use std::collections::VecDeque;
 use std::mem::replace;
-use std::sync::{Arc, Mutex};
-use std::thread::{park, park_timeout, Builder, JoinHandle};
-use std::time::Instant;
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::{park, park_timeout, Builder, JoinHandle, Thread};
+use std::time::{Duration, Instant};
 
 use crate::dependencies::DependencyDefault;
 use crate::effects::delay::State;
@@ -42,6 +42,7 @@ impl Shared {
 pub struct Reactor {
     shared: Arc<Mutex<Shared>>,
     handle: Option<JoinHandle<()>>,
+    clock: Option<Clock>,
 }
 
 impl Default for Reactor {
@@ -66,12 +67,21 @@ impl Default for Reactor {
         Self {
             shared,
             handle: Some(handle),
+            clock: None,
         }
     }
 }
 
 impl DependencyDefault for Reactor {}
 
+impl Drop for Reactor {
+    fn drop(&mut self) {
+        if let Some(handle) = &self.handle {
+            handle.thread().unpark(); // so that a `Clock`’s polling thread can exit
+        }
+    }
+}
+
 impl Reactor {
     pub(crate) fn new() -> Self {
         let shared = Arc::new(Mutex::<Shared>::default());
@@ -79,6 +89,48 @@ impl Reactor {
         Self {
             shared,
             handle: None,
+            clock: None,
+        }
+    }
+
+    /// A `Reactor` whose `Delay`s are ready once `clock` reaches them.
+    ///
+    /// A scaled `clock` is polled by its own thread, which exits once the `Reactor`
+    /// is dropped; a manual one whenever it is advanced.
+    pub(crate) fn with_clock(clock: &Clock) -> Self {
+        let shared = Arc::new(Mutex::<Shared>::default());
+
+        let handle = clock.0.rate.map(|rate| {
+            let (remote, clock) = (Arc::downgrade(&shared), clock.clone());
+
+            Builder::new()
+                .name(std::any::type_name::<Self>().into())
+                .spawn(move || {
+                    while let Some(shared) = remote.upgrade() {
+                        let now = clock.now();
+                        let next = Shared::poll(now, &shared);
+                        drop(shared);
+
+                        match next {
+                            None => park(),
+                            Some(when) => {
+                                park_timeout(when.saturating_duration_since(now).div_f64(rate))
+                            }
+                        }
+                    }
+                })
+                .expect("scheduler thread")
+        });
+
+        let thread = handle.as_ref().map(|handle| handle.thread().clone());
+        let mut reactors = clock.0.reactors.lock().unwrap();
+        reactors.push((Arc::downgrade(&shared), thread));
+        drop(reactors);
+
+        Self {
+            shared,
+            handle,
+            clock: Some(clock.clone()),
         }
     }
 
@@ -86,6 +138,19 @@ impl Reactor {
         Shared::poll(now, &self.shared).unwrap_or(now)
     }
 
+    /// When each of the `Delay`s still waiting will be ready; soonest first.
+    ///
+    /// `Delay`s that have been dropped, such as those of a cancelled `Task`, are not included.
+    pub(crate) fn pending(&self) -> Vec<Instant> {
+        let shared = self.shared.lock().unwrap();
+        let waiting = shared
+            .queue
+            .iter()
+            .filter(|(_, state)| Arc::strong_count(state) > 1);
+
+        waiting.map(|(instant, _)| instant).collect()
+    }
+
     #[inline(never)]
     pub(crate) fn add(&self, new: Instant, state: Arc<Mutex<State>>) {
         let mut shared = self.shared.lock().unwrap();
@@ -96,11 +161,144 @@ impl Reactor {
         match (&self.handle, next) {
             (Some(handle), None) => handle.thread().unpark(), // no `unpark` is scheduled yet
             (Some(handle), Some(pending)) if new < pending => handle.thread().unpark(),
+            (None, _) => {
+                if let Some(clock) = &self.clock {
+                    Shared::poll(clock.now(), &self.shared); // the clock may already be past it
+                }
+            }
             _ => {}
         }
     }
 }
 
+/// A controllable source of time for a [`Store`]’s timers; in place of the wall clock.
+///
+/// A `manual` clock only moves when it is [`advance`]d, while a `scaled` one runs at a
+/// multiple of the wall clock’s speed. Either way, long-running schedules can be
+/// exercised quickly by a full runtime; for simulations, replays or integration tests.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use composable::*;
+/// #
+/// #[derive(Default)]
+/// struct State {
+///     n: usize,
+///     timer: Option<Task>,
+/// }
+///
+/// #[derive(Clone, Debug)]
+/// enum Action {
+///     Start,
+///     Tick,
+/// }
+///
+/// impl Reducer for State {
+///     type Action = Action;
+///     type Output = usize;
+///
+///     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+///         match action {
+///             Action::Start => {
+///                 self.timer = Some(send.after(Duration::from_secs(60 * 60), Action::Tick));
+///             }
+///             Action::Tick => self.n += 1,
+///         }
+///     }
+/// }
+/// #
+/// # impl From<State> for usize {
+/// #     fn from(state: State) -> usize {
+/// #         state.n
+/// #     }
+/// # }
+///
+/// let clock = Clock::manual();
+/// let store = Store::builder()
+///     .clock(clock.clone())
+///     .with_initial(State::default());
+///
+/// futures::executor::block_on(store.send_and_wait(Action::Start));
+/// clock.advance(Duration::from_secs(60 * 60)); // an hour passes, instantly
+///
+/// assert_eq!(store.into_inner(), 1);
+/// ```
+///
+/// [`Store`]: crate::Store
+/// [`advance`]: Clock::advance
+#[derive(Clone)]
+pub struct Clock(Arc<Timeline>);
+
+struct Timeline {
+    start: Instant,
+    rate: Option<f64>,
+    offset: Mutex<Duration>,
+    reactors: Mutex<Vec<Driven>>,
+}
+
+/// A `Reactor` driven by a `Clock`, and the thread polling it; if it has one.
+type Driven = (Weak<Mutex<Shared>>, Option<Thread>);
+
+impl Clock {
+    /// A `Clock` that starts at the current time, and only moves when it is advanced.
+    pub fn manual() -> Self {
+        Self::new(None)
+    }
+
+    /// A `Clock` that starts at the current time, and runs `rate` times faster than the
+    /// wall clock; `10.0` being ten times the speed.
+    ///
+    /// # Panics
+    ///
+    /// If `rate` is not a positive, finite, number.
+    pub fn scaled(rate: f64) -> Self {
+        assert!(
+            rate.is_finite() && rate > 0.0,
+            "a Clock’s rate must be positive and finite"
+        );
+
+        Self::new(Some(rate))
+    }
+
+    fn new(rate: Option<f64>) -> Self {
+        Self(Arc::new(Timeline {
+            start: Instant::now(),
+            rate,
+            offset: Mutex::default(),
+            reactors: Mutex::default(),
+        }))
+    }
+
+    /// The current time, as far as this `Clock` is concerned.
+    pub fn now(&self) -> Instant {
+        let offset = *self.0.offset.lock().unwrap();
+        let elapsed = self
+            .0
+            .rate
+            .map_or(Duration::ZERO, |rate| self.0.start.elapsed().mul_f64(rate));
+
+        self.0.start + offset + elapsed
+    }
+
+    /// Moves the `Clock` forward by `duration`; readying any timers that it passes.
+    pub fn advance(&self, duration: Duration) {
+        *self.0.offset.lock().unwrap() += duration;
+        let now = self.now();
+
+        let mut reactors = self.0.reactors.lock().unwrap();
+        reactors.retain(|(shared, _)| shared.strong_count() > 0);
+        let reactors = reactors.clone();
+
+        for (shared, thread) in reactors {
+            match (thread, shared.upgrade()) {
+                (Some(thread), _) => thread.unpark(), // its thread polls with the new `now`
+                (None, Some(shared)) => drop(Shared::poll(now, &shared)),
+                (None, None) => {}
+            }
+        }
+    }
+}
+
 pub(crate) struct Queue<Key, Value> {
     deque: VecDeque<(Reverse<Key>, Value)>,
 }
@@ -115,6 +313,11 @@ impl<Key, Value> Default for Queue<Key, Value> {
 }
 
 impl<Key: Copy, Value> Queue<Key, Value> {
+    /// The entries; in the order they will be drained.
+    pub fn iter(&self) -> impl Iterator<Item = (Key, &Value)> {
+        self.deque.iter().rev().map(|kv| (kv.0 .0, &kv.1))
+    }
+
     pub fn peek_next(&self) -> Option<Key> {
         self.deque.back().map(|kv| kv.0 .0)
     }
@@ -1,12 +1,23 @@
-use std::thread::Thread;
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::BTreeMap;
+use std::future::poll_fn;
+use std::ops::Range;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::task::Context;
 
 use futures::executor::LocalSpawner;
-use futures::future::RemoteHandle;
-use futures::task::LocalSpawnExt;
-use futures::{pin_mut, Stream, StreamExt};
+use futures::future::{AbortHandle, Abortable, RemoteHandle};
+use futures::task::{waker, ArcWake, AtomicWaker, LocalSpawnExt};
+use futures::{pin_mut, Future, FutureExt, Stream, StreamExt};
 
 use crate::dependencies::Dependency;
-use crate::store::channel::WeakSender;
+use crate::store::cause::Cause;
+use crate::store::channel::{Priority, WeakSender};
+use crate::store::Control;
 
 /// Asynchronous work being performed by a `Store`.
 ///
@@ -19,11 +30,25 @@ use crate::store::channel::WeakSender;
 pub struct Task {
     pub(crate) handle: Option<RemoteHandle<()>>,
     pub(crate) when: Option<std::time::Instant>,
+    detached: Option<Arc<AtomicBool>>, // shared with its entry in the `Store`’s `Tasks`
 }
 
 impl Task {
     /// Detaches the task; leaving its [`Future`][`std::future`] running in the background.
+    ///
+    /// A `Store` does not wait for its detached tasks when it is shut down; they are
+    /// cancelled once all of its other tasks have finished.
     pub fn detach(self) {
+        if let Some(detached) = &self.detached {
+            detached.store(true, Ordering::Release);
+        }
+
+        self.forget()
+    }
+
+    /// Leaves the task running in the background; but, unlike a detached task, it is
+    /// still waited for when the `Store` is shut down.
+    pub(crate) fn forget(self) {
         if let Some(handle) = self.handle {
             handle.forget()
         }
@@ -36,23 +61,31 @@ impl Task {
 
     pub(crate) fn new<Action: 'static, S: Stream<Item = Action> + 'static>(stream: S) -> Self {
         // Only called by “root” `Effects`, so it will be the same `Action` as used by the `Store`
-        let handle = Dependency::<Executor<Result<Action, Thread>>>::new() //
-            .and_then(|executor| match executor.actions.upgrade() {
-                None => None,
-                Some(sender) => executor
-                    .spawner
-                    .spawn_local_with_handle(async move {
-                        pin_mut!(stream);
-                        while let Some(action) = stream.next().await {
-                            sender.send(Ok(action));
-                        }
-                    })
-                    .ok(),
+        let task = Dependency::<Executor<Result<Action, Control>>>::new() //
+            .and_then(|executor| {
+                let sender = executor.actions.upgrade()?;
+                let priority = Dependency::<Priority>::new().copied().unwrap_or_default();
+
+                let future = Cause::task(async move {
+                    pin_mut!(stream);
+                    while let Some(action) = stream.next().await {
+                        sender.send_async(priority, Ok(action)).await;
+                    }
+                });
+
+                executor.tasks.spawn(&executor.spawner, future)
             });
 
+        // may return a `Task { handle: None }` while the `Store` is shutting down
+        task.unwrap_or_else(Task::none)
+    }
+
+    /// A `Task` that was never started.
+    pub(crate) fn none() -> Self {
         Task {
-            handle, // may return a `Task { handle: None }` while the `Store` is shutting down
+            handle: None,
             when: None,
+            detached: None,
         }
     }
 }
@@ -60,10 +93,205 @@ impl Task {
 pub(crate) struct Executor<Action> {
     pub(crate) spawner: LocalSpawner,
     pub(crate) actions: WeakSender<Action>,
+    pub(crate) tasks: Rc<Tasks>,
+}
+
+// Using `#[derive(Clone)]` adds a `Clone` requirement to all `Action`s
+impl<Action> Clone for Executor<Action> {
+    fn clone(&self) -> Self {
+        Self {
+            spawner: self.spawner.clone(),
+            actions: self.actions.clone(),
+            tasks: self.tasks.clone(),
+        }
+    }
 }
 
 impl<Action> Executor<Action> {
     pub(crate) fn new(spawner: LocalSpawner, actions: WeakSender<Action>) -> Self {
-        Self { spawner, actions }
+        Self {
+            spawner,
+            actions,
+            tasks: Default::default(),
+        }
+    }
+}
+
+/// The `Task`s still running in a `Store`’s runtime; detached or not.
+#[derive(Default)]
+pub(crate) struct Tasks {
+    live: RefCell<BTreeMap<usize, (AbortHandle, Arc<AtomicBool>)>>,
+    next: Cell<usize>,
+    waiting: RefCell<Vec<(Range<usize>, Notify)>>,
+    cancelled: Cell<bool>,
+    shutdown: Cell<bool>,
+    notify: OnceCell<Arc<AtomicWaker>>,
+}
+
+/// Called once a range of tasks have all finished.
+type Notify = Box<dyn FnOnce()>;
+
+/// Whether any of the `tasks` (by id) that have not been detached are still running.
+fn running(live: &BTreeMap<usize, (AbortHandle, Arc<AtomicBool>)>, tasks: Range<usize>) -> bool {
+    let mut entries = live.range(tasks).map(|(_, entry)| entry);
+    entries.any(|(_, detached)| !detached.load(Ordering::Acquire))
+}
+
+impl Tasks {
+    /// Runs `future` as a new task, unless the runtime has already cancelled its tasks.
+    pub(crate) fn spawn(
+        self: &Rc<Self>,
+        spawner: &LocalSpawner,
+        future: impl Future<Output = ()> + 'static,
+    ) -> Option<Task> {
+        let (abort, registration) = AbortHandle::new_pair();
+        let detached = Arc::new(AtomicBool::new(false));
+        let live = self.insert(abort, detached.clone())?;
+
+        let future = async move {
+            let _live = live; // deregisters the task however the future ends
+            future.await
+        };
+
+        let future: Pin<Box<dyn Future<Output = ()>>> = match self.notify.get() {
+            Some(notify) => Box::pin(notifying(future, notify.clone())),
+            None => Box::pin(future),
+        };
+
+        let handle = spawner
+            .spawn_local_with_handle(Abortable::new(future, registration).map(drop))
+            .ok()?;
+
+        Some(Task {
+            handle: Some(handle),
+            when: None,
+            detached: Some(detached),
+        })
+    }
+
+    /// Registers a new task, unless the runtime has already cancelled its tasks.
+    fn insert(self: &Rc<Self>, abort: AbortHandle, detached: Arc<AtomicBool>) -> Option<Live> {
+        if self.cancelled.get() {
+            return None;
+        }
+
+        let id = self.next.replace(self.next.get() + 1);
+        self.live.borrow_mut().insert(id, (abort, detached));
+
+        Some(Live {
+            tasks: self.clone(),
+            id,
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.live.borrow().len()
+    }
+
+    /// The id that the next task started will be given.
+    pub(crate) fn next(&self) -> usize {
+        self.next.get()
+    }
+
+    /// Also wakes `notify` whenever one of the tasks started from now on is woken.
+    pub(crate) fn notify(&self, notify: Arc<AtomicWaker>) {
+        self.notify.set(notify).ok();
+    }
+
+    /// Notifies `idle` once there are no tasks running, other than detached ones; which
+    /// may be immediately.
+    pub(crate) fn when_idle(&self, idle: Sender<()>) {
+        self.when_finished(0..usize::MAX, move || idle.send(()).unwrap_or_default())
+    }
+
+    /// Calls `f` once none of the `tasks` (by id) are running, other than detached ones;
+    /// which may be immediately.
+    pub(crate) fn when_finished(&self, tasks: Range<usize>, f: impl FnOnce() + 'static) {
+        match running(&self.live.borrow(), tasks.clone()) {
+            false => f(),
+            true => self.waiting.borrow_mut().push((tasks, Box::new(f))),
+        }
+    }
+
+    /// Aborts the detached tasks once all of the other tasks have finished; including
+    /// any started in the meantime.
+    pub(crate) fn shutdown(&self) {
+        self.shutdown.set(true);
+        self.settle();
+    }
+
+    /// Notifies those waiting for tasks that have since finished, or been detached; and,
+    /// once shutting down, aborts the detached tasks if no others are running.
+    pub(crate) fn settle(&self) {
+        let live = self.live.borrow();
+        let (finished, waiting) = self
+            .waiting
+            .take()
+            .into_iter()
+            .partition::<Vec<_>, _>(|(tasks, _)| !running(&live, tasks.clone()));
+
+        self.waiting.replace(waiting);
+
+        if self.shutdown.get() && !running(&live, 0..usize::MAX) {
+            for (abort, _) in live.values() {
+                abort.abort();
+            }
+        }
+
+        drop(live);
+
+        for (_, notify) in finished {
+            notify();
+        }
+    }
+
+    /// Cancels all of the running tasks, and any that would have been started afterward.
+    pub(crate) fn cancel(&self) {
+        self.cancelled.set(true);
+        self.abort();
+    }
+
+    /// Cancels all of the running tasks; but not any started afterward.
+    pub(crate) fn abort(&self) {
+        for (abort, _) in self.live.borrow().values() {
+            abort.abort();
+        }
+    }
+}
+
+/// Polls `future` with a waker that also wakes `notify`; so that the owner of the
+/// executor running it knows when it can make progress again.
+pub(crate) fn notifying<F: Future>(
+    future: F,
+    notify: Arc<AtomicWaker>,
+) -> impl Future<Output = F::Output> {
+    let mut future = Box::pin(future);
+
+    poll_fn(move |cx| {
+        let both = waker(Arc::new(Both(cx.waker().clone(), notify.clone())));
+        future.as_mut().poll(&mut Context::from_waker(&both))
+    })
+}
+
+/// Wakes both the executor’s waker, and the waker of its owner.
+struct Both(std::task::Waker, Arc<AtomicWaker>);
+
+impl ArcWake for Both {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.wake_by_ref();
+        arc_self.1.wake();
+    }
+}
+
+/// Deregisters its task when dropped.
+struct Live {
+    tasks: Rc<Tasks>,
+    id: usize,
+}
+
+impl Drop for Live {
+    fn drop(&mut self) {
+        self.tasks.live.borrow_mut().remove(&self.id);
+        self.tasks.settle();
     }
 }
@@ -8,9 +8,15 @@
 #[doc(no_inline)]
 pub use derive_macros::*;
 #[doc(inline)]
-pub use effects::{Interval, Task};
-pub use reducer::Reducer;
-pub use store::{testing::TestClock, testing::TestStore, Store};
+pub use effects::{Clock, Interval, Task};
+pub use reducer::{history, persistence, Reducer};
+#[doc(inline)]
+pub use store::recording;
+pub use store::testing::{Exhaustivity, TestClock, TestStore};
+pub use store::{
+    CorrelationId, Health, Metrics, StoreBuilder, Supervision, Ticket, Timing, Traced,
+};
+pub use store::{LocalStore, Middleware, Overflow, Priority, ShutdownError, Store};
 
 pub mod dependencies;
 /// Optional view feature.
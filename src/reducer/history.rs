@@ -0,0 +1,330 @@
+//! Undo and redo for a [`Reducer`]’s state.
+//!
+//! Wrapping a `Reducer` in a [`History`] snapshots its state as actions are reduced, so that
+//! the state can be moved backward (and forward again) through those snapshots.
+//!
+//! As a `History` is itself a `Reducer`, it can be used as a field of a [`RecursiveReducer`];
+//! making only that part of the application’s state undoable.
+//!
+//! ```rust
+//! # use std::time::Duration;
+//! # use composable::*;
+//! use composable::history::{self, History};
+//!
+//! #[derive(Clone, Debug, Default, PartialEq)]
+//! struct Document {
+//!     text: String,
+//! }
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Edit {
+//!     Type(char),
+//! }
+//!
+//! impl Reducer for Document {
+//!     type Action = Edit;
+//!     type Output = Self;
+//!
+//!     fn reduce(&mut self, action: Edit, send: impl Effects<Edit>) {
+//!         match action {
+//!             Edit::Type(ch) => self.text.push(ch),
+//!         }
+//!     }
+//! }
+//!
+//! use history::Action::*;
+//!
+//! let mut store = TestStore::with_initial(History::new(Document::default()));
+//!
+//! store.send(Reduce(Edit::Type('a')), |state| state.text.push('a'));
+//! store.advance(Duration::ZERO); // each batch of actions is an undo step…
+//! store.recv(Commit, |_| {});
+//!
+//! store.send(Reduce(Edit::Type('b')), |state| state.text.push('b'));
+//! store.advance(Duration::ZERO);
+//! store.recv(Commit, |_| {});
+//!
+//! store.send(Undo, |state| state.text = "a".into());
+//! store.send(Undo, |state| state.text.clear());
+//! store.send(Redo, |state| state.text.push('a'));
+//!
+//! store.send(Reduce(Edit::Type('c')), |state| state.text.push('c')); // …and clears the redos
+//! store.send(Redo, |_| {});
+//! ```
+//!
+//! [`RecursiveReducer`]: crate::derive_macros
+
+use std::mem::replace;
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+use crate::effects::Interval;
+use crate::{Effects, Reducer, Task};
+
+/// The `Action`s of a [`History`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action<A> {
+    /// An action for the wrapped `Reducer`.
+    Reduce(A),
+    /// Returns to the state before the most recent undo step.
+    Undo,
+    /// Reapplies the most recently undone step.
+    Redo,
+    /// Moves directly to the snapshot at `index`; where `0` is the oldest.
+    ///
+    /// See [`History::index`] and [`History::snapshots`].
+    Jump(usize),
+    /// Ends the current undo step.
+    ///
+    /// Sent by the `History` itself once no more actions have been reduced for its
+    /// [coalescing interval](History::coalescing).
+    Commit,
+}
+
+impl<A> From<A> for Action<A> {
+    fn from(action: A) -> Self {
+        Action::Reduce(action)
+    }
+}
+
+/// A [`Reducer`] that keeps snapshots of another `Reducer`’s state so that its actions may
+/// be undone and redone.
+///
+/// Each undo step is a batch of actions; an action sent to the `Store` along with all of
+/// the follow-up actions it sends. Rapid batches may be coalesced into a single undo step
+/// with [`coalescing`][History::coalescing], which uses the same semantics as
+/// [`Scheduler::debounce`][`crate::effects::Scheduler::debounce`].
+///
+/// See the [module level documentation](self) for more.
+#[derive(Debug)]
+pub struct History<R> {
+    present: R,
+    past: Vec<R>,
+    future: Vec<R>,
+
+    limit: Option<usize>,
+    coalescing: Duration,
+    pending: Option<Task>, // the debounced `Commit` of an open undo step
+}
+
+impl<R: Clone> Clone for History<R> {
+    fn clone(&self) -> Self {
+        History {
+            present: self.present.clone(),
+            past: self.past.clone(),
+            future: self.future.clone(),
+            limit: self.limit,
+            coalescing: self.coalescing,
+            pending: None, // a `Task` cannot be cloned
+        }
+    }
+}
+
+/// Only the present states are compared; so that a [`TestStore`][`crate::TestStore`]
+/// can assert changes to them just as it would without the `History`.
+impl<R: PartialEq> PartialEq for History<R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.present == other.present
+    }
+}
+
+impl<R: Default> Default for History<R> {
+    fn default() -> Self {
+        Self::new(R::default())
+    }
+}
+
+impl<R> Deref for History<R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        &self.present
+    }
+}
+
+/// Changes made through `DerefMut` are not recorded as an undo step.
+impl<R> DerefMut for History<R> {
+    fn deref_mut(&mut self) -> &mut R {
+        &mut self.present
+    }
+}
+
+impl<R> History<R> {
+    /// Creates a new `History` with `present` as its current state.
+    pub fn new(present: R) -> Self {
+        History {
+            present,
+            past: Vec::new(),
+            future: Vec::new(),
+            limit: None,
+            coalescing: Duration::ZERO,
+            pending: None,
+        }
+    }
+
+    /// Coalesces batches of actions that are reduced within `interval` of each
+    /// other into a single undo step.
+    pub fn coalescing(mut self, interval: Duration) -> Self {
+        self.coalescing = interval;
+        self
+    }
+
+    /// Keeps no more than `limit` undo steps; discarding the oldest.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self.trim();
+        self
+    }
+
+    /// The current state.
+    pub fn present(&self) -> &R {
+        &self.present
+    }
+
+    /// Returns `true` if there is an undo step to return to.
+    pub fn can_undo(&self) -> bool {
+        !self.past.is_empty()
+    }
+
+    /// Returns `true` if there is an undone step to reapply.
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+
+    /// The index of the current state among all of the snapshots; where `0` is the oldest.
+    pub fn index(&self) -> usize {
+        self.past.len()
+    }
+
+    /// The number of snapshots; including the current state.
+    pub fn snapshots(&self) -> usize {
+        self.past.len() + 1 + self.future.len()
+    }
+
+    /// Consumes the `History` and returns its current state.
+    pub fn into_inner(self) -> R {
+        self.present
+    }
+
+    fn undo(&mut self) -> bool {
+        match self.past.pop() {
+            None => false,
+            Some(previous) => {
+                let present = replace(&mut self.present, previous);
+                self.future.push(present);
+                true
+            }
+        }
+    }
+
+    fn redo(&mut self) -> bool {
+        match self.future.pop() {
+            None => false,
+            Some(next) => {
+                let present = replace(&mut self.present, next);
+                self.past.push(present);
+                true
+            }
+        }
+    }
+
+    fn trim(&mut self) {
+        if let Some(limit) = self.limit {
+            let excess = self.past.len().saturating_sub(limit);
+            self.past.drain(..excess);
+        }
+    }
+}
+
+impl<R> Reducer for History<R>
+where
+    R: Reducer + Clone,
+    <R as Reducer>::Action: Clone + 'static,
+{
+    type Action = Action<<R as Reducer>::Action>;
+    type Output = Self;
+
+    fn reduce(&mut self, action: Self::Action, send: impl Effects<Self::Action>) {
+        match action {
+            Action::Reduce(action) => {
+                if self.pending.is_none() {
+                    // the start of a new undo step
+                    self.past.push(self.present.clone());
+                    self.future.clear();
+                    self.trim();
+                }
+
+                self.present.reduce(action, send.scope());
+
+                let interval = Interval::Trailing(self.coalescing);
+                send.debounce(Action::Commit, &mut self.pending, interval);
+            }
+            Action::Commit => {
+                self.pending = None;
+            }
+            Action::Undo => {
+                self.pending = None; // closes any open undo step
+                self.undo();
+            }
+            Action::Redo => {
+                self.pending = None;
+                self.redo();
+            }
+            Action::Jump(index) => {
+                self.pending = None;
+                while index < self.index() && self.undo() {}
+                while index > self.index() && self.redo() {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{TestClock, TestStore};
+
+    use super::Action::*;
+    use super::*;
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct Counter(usize);
+
+    impl Reducer for Counter {
+        type Action = ();
+        type Output = Self;
+
+        fn reduce(&mut self, _action: (), _send: impl Effects<()>) {
+            self.0 += 1;
+        }
+    }
+
+    #[test]
+    fn test_coalescing_and_jumps() {
+        let history = History::new(Counter::default()).coalescing(Duration::from_secs(1));
+        let mut store = TestStore::with_initial(history);
+
+        // three actions, each within a second of the last, are one undo step
+        store.send(Reduce(()), |state| state.0 = 1);
+        store.advance(Duration::from_millis(500));
+        store.send(Reduce(()), |state| state.0 = 2);
+        store.advance(Duration::from_millis(500));
+        store.send(Reduce(()), |state| state.0 = 3);
+        store.advance(Duration::from_secs(1));
+        store.recv(Commit, |_| {});
+
+        store.send(Reduce(()), |state| state.0 = 4);
+        store.advance(Duration::from_secs(1));
+        store.recv(Commit, |_| {});
+
+        store.send(Undo, |state| state.0 = 3);
+        store.send(Undo, |state| state.0 = 0);
+        store.send(Undo, |_| {});
+
+        store.send(Jump(2), |state| state.0 = 4);
+        store.send(Jump(1), |state| state.0 = 3);
+
+        let history = store.into_inner();
+        assert_eq!((history.index(), history.snapshots()), (1, 3));
+        assert!(history.can_undo() && history.can_redo());
+    }
+}
@@ -2,6 +2,9 @@ use std::ops::DerefMut;
 
 use crate::Effects;
 
+pub mod history;
+pub mod persistence;
+
 /// `Reducer`s are responsible for updating a `Store`’s state in response to its `Action`s.
 pub trait Reducer {
     /// All of the possible actions that can be used to modify state.
@@ -29,6 +32,7 @@ pub trait Reducer {
     ///     n: Rc<Cell<usize>>, // Rc<Cell<…>> is not Send
     /// };
     ///
+    /// # #[derive(Debug)]
     /// enum Action { /* … */ }
     ///
     /// impl Reducer for State {
@@ -0,0 +1,483 @@
+//! Saving a [`Reducer`]’s state, so that it survives the application restarting.
+//!
+//! Wrapping a `Reducer` in a [`Persisted`] saves (the parts of) its state that it chooses to
+//! [`Persist`] to a [`Storage`] backend after its actions have been reduced; throttled with
+//! [`Scheduler::throttle`] semantics so that a burst of actions is only saved once. The saved
+//! state is restored when the `Persisted` is created, which is usually in [`Store::new`].
+//!
+//! ```rust
+//! # use composable::*;
+//! use composable::persistence::{MemoryStorage, Persist, Persisted};
+//!
+//! #[derive(Clone, Debug, Default, PartialEq)]
+//! struct Settings {
+//!     volume: u8,
+//!     playing: bool, // not persisted
+//! }
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Action {
+//!     Volume(u8),
+//! }
+//!
+//! impl Reducer for Settings {
+//!     type Action = Action;
+//!     type Output = Self;
+//!
+//!     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+//!         match action {
+//!             Action::Volume(volume) => self.volume = volume,
+//!         }
+//!     }
+//! }
+//!
+//! impl Persist for Settings {
+//!     const VERSION: u32 = 1;
+//!
+//!     fn save(&self) -> Vec<u8> {
+//!         vec![self.volume]
+//!     }
+//!
+//!     fn restore(&mut self, bytes: &[u8]) -> bool {
+//!         match bytes {
+//!             [volume] => self.volume = *volume,
+//!             _ => return false,
+//!         }
+//!
+//!         true
+//!     }
+//! }
+//!
+//! let storage = MemoryStorage::default(); // or a `FileStorage`
+//!
+//! let backend = storage.clone();
+//! let store = Store::new(|| Persisted::new(Settings::default(), backend));
+//! store.send(Action::Volume(11));
+//! store.into_inner(); // waits for the (throttled) save
+//!
+//! let backend = storage.clone();
+//! let store = Store::new(|| Persisted::new(Settings::default(), backend));
+//! assert_eq!(store.with_state(|settings| settings.volume), 11);
+//! # store.into_inner();
+//! ```
+//!
+//! [`Scheduler::throttle`]: crate::effects::Scheduler::throttle
+//! [`Store::new`]: crate::Store::new
+
+use std::fmt::{Debug, Formatter};
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Write};
+use std::ops::{Deref, DerefMut};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::effects::Interval;
+use crate::{Effects, Reducer, Task};
+
+/// The parts of a [`Reducer`]’s state that are saved by a [`Persisted`].
+pub trait Persist {
+    /// The version of the format returned by [`save`][`Persist::save`].
+    ///
+    /// It should be incremented whenever that format changes; with a matching
+    /// [`migrate`][`Persist::migrate`] step from the previous version.
+    const VERSION: u32;
+
+    /// The state to be saved.
+    fn save(&self) -> Vec<u8>;
+
+    /// Restores state that was previously saved; in the current [`VERSION`][`Persist::VERSION`].
+    ///
+    /// Returns `false` if the `bytes` could not be restored; in which case the state
+    /// should be left as it was.
+    fn restore(&mut self, bytes: &[u8]) -> bool;
+
+    /// Converts state that was saved in `version` into the format of the next version.
+    ///
+    /// Called as many times as needed to bring the saved state up to the current
+    /// [`VERSION`][`Persist::VERSION`]. Returning `None` discards the saved state.
+    fn migrate(version: u32, bytes: Vec<u8>) -> Option<Vec<u8>> {
+        let _ = (version, bytes);
+        None
+    }
+}
+
+/// State saved by a [`Persisted`], along with the version of its format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Saved {
+    /// The [`Persist::VERSION`] that the `bytes` were saved with.
+    pub version: u32,
+    /// The state, as returned by [`Persist::save`].
+    pub bytes: Vec<u8>,
+}
+
+/// Where a [`Persisted`] keeps its saved state.
+///
+/// `Storage` is used on the `Store`’s runtime thread.
+pub trait Storage {
+    /// The most recently stored state, if any.
+    fn load(&mut self) -> io::Result<Option<Saved>>;
+
+    /// Replaces the stored state.
+    fn store(&mut self, saved: &Saved) -> io::Result<()>;
+}
+
+/// [`Storage`] in a file.
+///
+/// The file is replaced, rather than overwritten, so that an interrupted save
+/// never leaves it incomplete. The replacement is written next to it first; with
+/// `.partial` appended to its name.
+#[derive(Clone, Debug)]
+pub struct FileStorage {
+    path: PathBuf,
+}
+
+impl FileStorage {
+    /// Creates a `FileStorage` that saves to the file at `path`.
+    ///
+    /// The file need not exist yet; nothing has been saved until it does. Its
+    /// directory must.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileStorage { path: path.into() }
+    }
+}
+
+impl Storage for FileStorage {
+    fn load(&mut self) -> io::Result<Option<Saved>> {
+        let contents = match fs::read(&self.path) {
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            contents => contents?,
+        };
+
+        match contents.split_first_chunk() {
+            None => Err(io::Error::new(ErrorKind::InvalidData, "missing version")),
+            Some((version, bytes)) => Ok(Some(Saved {
+                version: u32::from_le_bytes(*version),
+                bytes: bytes.to_vec(),
+            })),
+        }
+    }
+
+    fn store(&mut self, saved: &Saved) -> io::Result<()> {
+        let mut contents = saved.version.to_le_bytes().to_vec();
+        contents.extend_from_slice(&saved.bytes);
+
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".partial"); // not `with_extension`, which could replace a sibling
+        let temporary = PathBuf::from(temporary);
+
+        let mut file = File::create(&temporary)?;
+        file.write_all(&contents)?;
+        file.sync_all()?; // before the rename can make it visible
+
+        fs::rename(temporary, &self.path)
+    }
+}
+
+/// [`Storage`] in memory; for tests.
+///
+/// Clones share the same saved state.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStorage {
+    saved: Arc<Mutex<Option<Saved>>>,
+}
+
+impl MemoryStorage {
+    /// The most recently stored state, if any.
+    pub fn saved(&self) -> Option<Saved> {
+        self.saved
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load(&mut self) -> io::Result<Option<Saved>> {
+        Ok(self.saved())
+    }
+
+    fn store(&mut self, saved: &Saved) -> io::Result<()> {
+        let mut stored = self.saved.lock().unwrap_or_else(|err| err.into_inner());
+        *stored = Some(saved.clone());
+        Ok(())
+    }
+}
+
+/// The `Action`s of a [`Persisted`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action<A> {
+    /// An action for the wrapped `Reducer`.
+    Reduce(A),
+    /// Saves the wrapped `Reducer`’s state.
+    ///
+    /// Sent by the `Persisted` itself, once its [throttling interval](Persisted::throttling)
+    /// allows.
+    Save,
+}
+
+impl<A> From<A> for Action<A> {
+    fn from(action: A) -> Self {
+        Action::Reduce(action)
+    }
+}
+
+/// A [`Reducer`] that saves another `Reducer`’s state to [`Storage`], and restores it.
+///
+/// See the [module level documentation](self) for more.
+pub struct Persisted<R, S> {
+    state: R,
+    storage: S,
+
+    throttling: Duration,
+    pending: Option<Task>, // the throttled `Save`
+    error: Option<io::Error>,
+}
+
+impl<R: Debug, S: Debug> Debug for Persisted<R, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Persisted")
+            .field("state", &self.state)
+            .field("storage", &self.storage)
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R: Clone, S: Clone> Clone for Persisted<R, S> {
+    fn clone(&self) -> Self {
+        Persisted {
+            state: self.state.clone(),
+            storage: self.storage.clone(),
+            throttling: self.throttling,
+            pending: None, // the clone schedules its own saves
+            error: None,   // and has yet to fail at any
+        }
+    }
+}
+
+/// Only the states are compared; not their storage, nor any pending save or error.
+impl<R: PartialEq, S> PartialEq for Persisted<R, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.state == other.state
+    }
+}
+
+impl<R, S> Deref for Persisted<R, S> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        &self.state
+    }
+}
+
+/// Changes made through `DerefMut` are not saved until the next action is reduced.
+impl<R, S> DerefMut for Persisted<R, S> {
+    fn deref_mut(&mut self) -> &mut R {
+        &mut self.state
+    }
+}
+
+impl<R: Persist, S: Storage> Persisted<R, S> {
+    /// Creates a new `Persisted`; restoring any state previously saved in `storage`
+    /// into the `initial` state, after migrating it to the current version.
+    ///
+    /// If the saved state cannot be loaded, migrated or restored then `initial` is
+    /// used as it is and the reason, if any, is kept as the [`error`][Persisted::error].
+    pub fn new(mut initial: R, mut storage: S) -> Self {
+        let mut error = None;
+
+        match storage.load() {
+            Err(err) => error = Some(err),
+            Ok(None) => {}
+            Ok(Some(Saved { version, .. })) if version > R::VERSION => {
+                // saved by a newer version; which can’t be understood
+                let message = format!("saved with version {version}; newer than {}", R::VERSION);
+                error = Some(io::Error::new(ErrorKind::InvalidData, message));
+            }
+            Ok(Some(Saved { version, bytes })) => {
+                let migrated = (version..R::VERSION)
+                    .try_fold(bytes, |bytes, version| R::migrate(version, bytes));
+
+                if !migrated.is_some_and(|bytes| initial.restore(&bytes)) {
+                    let message = format!("could not restore version {version}");
+                    error = Some(io::Error::new(ErrorKind::InvalidData, message));
+                }
+            }
+        }
+
+        Persisted {
+            state: initial,
+            storage,
+            throttling: Duration::ZERO,
+            pending: None,
+            error,
+        }
+    }
+}
+
+impl<R, S> Persisted<R, S> {
+    /// Saves no more than once every `interval`; the last state within each interval
+    /// is the one saved.
+    pub fn throttling(mut self, interval: Duration) -> Self {
+        self.throttling = interval;
+        self
+    }
+
+    /// The most recent error from restoring or saving the state, if any.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    /// Consumes the `Persisted` and returns its current state.
+    pub fn into_inner(self) -> R {
+        self.state
+    }
+}
+
+impl<R, S> Reducer for Persisted<R, S>
+where
+    R: Reducer + Persist,
+    <R as Reducer>::Action: Clone + 'static,
+    S: Storage,
+{
+    type Action = Action<<R as Reducer>::Action>;
+    type Output = Self;
+
+    fn reduce(&mut self, action: Self::Action, send: impl Effects<Self::Action>) {
+        match action {
+            Action::Reduce(action) => {
+                self.state.reduce(action, send.scope());
+
+                let interval = Interval::Trailing(self.throttling);
+                send.throttle(Action::Save, &mut self.pending, interval);
+            }
+            Action::Save => {
+                let saved = Saved {
+                    version: R::VERSION,
+                    bytes: self.state.save(),
+                };
+
+                self.error = self.storage.store(&saved).err();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{TestClock, TestStore};
+
+    use super::Action::*;
+    use super::*;
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct Counter(u16);
+
+    impl Reducer for Counter {
+        type Action = ();
+        type Output = Self;
+
+        fn reduce(&mut self, _action: (), _send: impl Effects<()>) {
+            self.0 += 1;
+        }
+    }
+
+    impl Persist for Counter {
+        const VERSION: u32 = 2; // version 1 was a single byte
+
+        fn save(&self) -> Vec<u8> {
+            self.0.to_le_bytes().to_vec()
+        }
+
+        fn restore(&mut self, bytes: &[u8]) -> bool {
+            let Ok(bytes) = bytes.try_into() else {
+                return false;
+            };
+
+            self.0 = u16::from_le_bytes(bytes);
+            true
+        }
+
+        fn migrate(version: u32, bytes: Vec<u8>) -> Option<Vec<u8>> {
+            match (version, bytes.as_slice()) {
+                (1, [n]) => Some(vec![*n, 0]),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_throttled_saves() {
+        let storage = MemoryStorage::default();
+        let persisted = Persisted::new(Counter::default(), storage.clone());
+        let mut store = TestStore::with_initial(persisted.throttling(Duration::from_secs(1)));
+
+        store.send(Reduce(()), |state| state.0 = 1);
+        store.send(Reduce(()), |state| state.0 = 2);
+        store.advance(Duration::from_secs(1));
+        store.recv(Save, |_| {});
+
+        let saved = Saved {
+            version: 2,
+            bytes: vec![2, 0],
+        };
+        assert_eq!(storage.saved(), Some(saved));
+
+        let restored = Persisted::new(Counter::default(), storage);
+        assert_eq!(restored.0, 2);
+        assert!(restored.error().is_none());
+    }
+
+    #[test]
+    fn test_migrations() {
+        let mut storage = MemoryStorage::default();
+        let saved = |version, bytes| Saved { version, bytes };
+
+        storage.store(&saved(1, vec![7])).unwrap();
+        let restored = Persisted::new(Counter::default(), storage.clone());
+        assert_eq!((restored.0, restored.error().is_none()), (7, true));
+
+        storage.store(&saved(3, vec![7, 0, 0])).unwrap(); // from the future
+        let restored = Persisted::new(Counter::default(), storage.clone());
+        assert_eq!((restored.0, restored.error().is_some()), (0, true));
+
+        storage.store(&saved(0, vec![7])).unwrap(); // no migration
+        let restored = Persisted::new(Counter::default(), storage);
+        assert_eq!((restored.0, restored.error().is_some()), (0, true));
+    }
+
+    #[test]
+    fn test_file_storage() {
+        let directory = std::env::temp_dir().join(format!("composable-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+
+        let path = directory.join("counter.v2");
+        let sibling = directory.join("counter.partial"); // `with_extension` would replace it
+        fs::write(&sibling, b"untouched").unwrap();
+
+        let mut storage = FileStorage::new(&path);
+        assert_eq!(storage.load().unwrap(), None);
+
+        let persisted = Persisted::new(Counter(512), storage.clone());
+        let mut store = TestStore::with_initial(persisted.throttling(Duration::from_secs(1)));
+
+        store.send(Reduce(()), |state| state.0 = 513);
+        store.advance(Duration::from_secs(1));
+        store.recv(Save, |_| {});
+
+        let saved = Saved {
+            version: 2,
+            bytes: vec![1, 2],
+        };
+        assert_eq!(storage.load().unwrap(), Some(saved));
+
+        let restored = Persisted::new(Counter::default(), storage);
+        assert_eq!((restored.0, restored.error().is_none()), (513, true));
+
+        assert_eq!(fs::read(&sibling).unwrap(), b"untouched");
+        assert!(!directory.join("counter.v2.partial").exists());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
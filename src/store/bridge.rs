@@ -0,0 +1,187 @@
+use crate::dependencies::Dependency;
+use crate::store::channel::WeakSender;
+use crate::store::middleware::Dropped;
+use crate::store::{Control, Middleware, Store};
+use crate::Reducer;
+
+impl<State: Reducer> Store<State> {
+    /// Sends every action that this `Store` reduces, and that `map` returns `Some` for, on
+    /// to the `other` `Store`; once this `Store` has reduced it.
+    ///
+    /// Actions that a [`Middleware`] drops are not reduced, and so are not forwarded.
+    ///
+    /// This is how a child `Store` (one per window, for example) tells an app-level
+    /// parent `Store` about its “delegate” actions; and how the parent’s actions can
+    /// be passed on to its children:
+    ///
+    /// ```rust
+    /// # use composable::*;
+    /// # #[derive(Default)]
+    /// # struct Child;
+    /// #
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum ChildAction {
+    ///     Close,
+    ///     Delegate(Delegate),
+    /// #   ThemeChanged,
+    /// }
+    ///
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Delegate {
+    ///     Closed,
+    /// }
+    /// #
+    /// # impl Reducer for Child {
+    /// #     type Action = ChildAction;
+    /// #     type Output = Self;
+    /// #
+    /// #     fn reduce(&mut self, action: ChildAction, send: impl Effects<ChildAction>) {
+    /// #         if let ChildAction::Close = action {
+    /// #             send.action(ChildAction::Delegate(Delegate::Closed));
+    /// #         }
+    /// #     }
+    /// # }
+    /// #
+    /// # #[derive(Default)]
+    /// # struct Parent { windows: usize }
+    /// #
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum ParentAction {
+    ///     WindowClosed,
+    ///     ThemeChanged,
+    /// }
+    /// #
+    /// # impl Reducer for Parent {
+    /// #     type Action = ParentAction;
+    /// #     type Output = Self;
+    /// #
+    /// #     fn reduce(&mut self, action: ParentAction, send: impl Effects<ParentAction>) {
+    /// #         if let ParentAction::WindowClosed = action {
+    /// #             self.windows += 1;
+    /// #         }
+    /// #     }
+    /// # }
+    ///
+    /// let parent = Store::<Parent>::default();
+    /// let child = Store::<Child>::default();
+    ///
+    /// child.forward(&parent, |action| match action {
+    ///     ChildAction::Delegate(Delegate::Closed) => Some(ParentAction::WindowClosed),
+    ///     _ => None,
+    /// });
+    ///
+    /// parent.forward(&child, |action| match action {
+    ///     ParentAction::ThemeChanged => Some(ChildAction::ThemeChanged),
+    ///     _ => None,
+    /// });
+    ///
+    /// child.send(ChildAction::Close);
+    /// # child.into_inner();
+    /// # assert_eq!(parent.into_inner().windows, 1);
+    /// ```
+    ///
+    /// Forwarding stops once the `other` `Store` has stopped. Forwarded actions never
+    /// block this `Store`, even if the `other` one is [`bounded`] and full.
+    ///
+    /// [`bounded`]: crate::StoreBuilder::bounded
+    pub fn forward<Other, F>(&self, other: &Store<Other>, map: F)
+    where
+        Other: Reducer,
+        <Other as Reducer>::Action: Send + 'static,
+        F: Fn(&<State as Reducer>::Action) -> Option<<Other as Reducer>::Action> + Send + 'static,
+        State: 'static,
+    {
+        self.add_middleware(Forward {
+            other: other.sender.downgrade(),
+            map,
+            pending: None,
+        })
+    }
+}
+
+/// The [`Middleware`] behind [`Store::forward`].
+struct Forward<Action, F> {
+    other: WeakSender<Result<Action, Control>>,
+    map: F,
+    pending: Option<Action>, // sent once the current action has been reduced, unless dropped
+}
+
+impl<State, Action, F> Middleware<State> for Forward<Action, F>
+where
+    State: Reducer,
+    F: Fn(&<State as Reducer>::Action) -> Option<Action>,
+{
+    fn before(&mut self, action: &<State as Reducer>::Action, _state: &State) -> bool {
+        self.pending = (self.map)(action);
+        true
+    }
+
+    fn after(&mut self, _state: &State) {
+        let pending = self.pending.take();
+        if Dependency::<Dropped>::new().is_some() {
+            return; // by a later layer
+        }
+
+        if let Some((other, action)) = self.other.upgrade().zip(pending) {
+            other.send_nonblocking(Ok(action));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(miri))]
+    use ntest_timeout::timeout;
+
+    use crate::store::runtime::tests::{Action, State};
+    use crate::{Middleware, Store};
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_forwarding() {
+        let parent = Store::<State>::default();
+        let child = Store::<State>::default();
+
+        use Action::*;
+        child.forward(&parent, |action| match action {
+            Internal(ch) => Some(External(ch.to_ascii_lowercase())),
+            External(_) => None,
+        });
+
+        child.send(External('1')); // sends `A`, `B`, `C` and `D`
+        child.into_inner();
+
+        let state = parent.into_inner();
+        assert_eq!(*state.characters.lock().unwrap(), ['a', 'b', 'c', 'd']);
+    }
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_dropped_actions_are_not_forwarded() {
+        let parent = Store::<State>::default();
+        let child = Store::<State>::default();
+
+        use Action::*;
+        child.forward(&parent, |action| match action {
+            Internal(ch) => Some(External(ch.to_ascii_lowercase())),
+            External(_) => None,
+        });
+        child.add_middleware(Filter('B'));
+
+        child.send(External('1')); // sends `A`, `B`, `C` and `D`
+        child.into_inner();
+
+        let state = parent.into_inner();
+        assert_eq!(*state.characters.lock().unwrap(), ['a', 'c', 'd']);
+    }
+
+    /// Drops the `Internal` action for its character.
+    struct Filter(char);
+
+    impl Middleware<State> for Filter {
+        fn before(&mut self, action: &Action, _state: &State) -> bool {
+            *action != Action::Internal(self.0)
+        }
+    }
+}
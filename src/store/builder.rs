@@ -0,0 +1,197 @@
+use crate::effects::Clock;
+use crate::store::cause::{Traced, Tracer};
+use crate::store::channel::{Bounds, Overflow};
+use crate::store::supervision::{OnPanic, Supervision};
+use crate::store::{Control, Store};
+use crate::Reducer;
+
+/// Configures a [`Store`] before it is started.
+///
+/// ```rust
+/// # use composable::*;
+/// # #[derive(Default)]
+/// # struct State;
+/// #
+/// # #[derive(Clone, Debug, PartialEq)]
+/// # enum Action { MouseMoved(i32, i32), Clicked }
+/// #
+/// # impl Reducer for State {
+/// #     type Action = Action;
+/// #     type Output = Self;
+/// #
+/// #     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {}
+/// # }
+/// #
+/// let store = Store::builder()
+///     .bounded(64, Overflow::coalesce_by_key(std::mem::discriminant))
+///     .with_initial(State::default());
+///
+/// store.send(Action::MouseMoved(0, 0));
+/// assert_eq!(store.dropped(), 0);
+/// ```
+pub struct StoreBuilder<State: Reducer> {
+    pub(crate) bounds: Option<Bounds<Result<<State as Reducer>::Action, Control>>>,
+    pub(crate) supervision: Supervision<State>,
+    pub(crate) on_panic: Option<OnPanic>,
+    pub(crate) tracer: Tracer<<State as Reducer>::Action>,
+    pub(crate) clock: Option<Clock>,
+}
+
+impl<State: Reducer> Default for StoreBuilder<State>
+where
+    <State as Reducer>::Action: Traced,
+{
+    fn default() -> Self {
+        StoreBuilder {
+            bounds: None,
+            supervision: Supervision::Stop,
+            on_panic: None,
+            tracer: Tracer::default(),
+            clock: None,
+        }
+    }
+}
+
+impl<State: Reducer> StoreBuilder<State> {
+    /// Limits the `Store` to holding `capacity` unprocessed actions; applying the
+    /// `overflow` policy to any more that are sent.
+    ///
+    /// Actions sent by [`Task`][`crate::Task`]s wait for space, rather than overflowing,
+    /// unless the policy is to drop or coalesce them.
+    ///
+    /// The number of actions dropped is reported by [`Store::dropped`].
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    pub fn bounded(
+        mut self,
+        capacity: usize,
+        overflow: Overflow<<State as Reducer>::Action>,
+    ) -> Self
+    where
+        <State as Reducer>::Action: 'static,
+    {
+        assert!(
+            capacity > 0,
+            "a Store must have room for at least one action"
+        );
+
+        let overflow = match overflow {
+            Overflow::Block => Overflow::Block,
+            Overflow::DropOldest => Overflow::DropOldest,
+            Overflow::DropNewest => Overflow::DropNewest,
+            Overflow::Coalesce(same) => Overflow::Coalesce(Box::new(
+                move |lhs, rhs| matches!((lhs, rhs), (Ok(lhs), Ok(rhs)) if same(lhs, rhs)),
+            )),
+        };
+
+        self.bounds = Some(Bounds {
+            capacity,
+            overflow,
+            applies: Result::is_ok, // `Control` messages are never dropped
+        });
+
+        self
+    }
+
+    /// Sets what the `Store` does when its [`Reducer`] panics.
+    ///
+    /// Panics in the `Store`’s [`Task`][`crate::Task`]s are not caught.
+    ///
+    /// ```rust
+    /// # use composable::*;
+    /// # #[derive(Clone, Default)]
+    /// # struct State { n: usize }
+    /// #
+    /// # #[derive(Clone, Debug, PartialEq)]
+    /// # enum Action { Increment, Panic }
+    /// #
+    /// # impl Reducer for State {
+    /// #     type Action = Action;
+    /// #     type Output = Self;
+    /// #
+    /// #     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+    /// #         self.n += 1;
+    /// #         if let Action::Panic = action {
+    /// #             panic!("a bad action");
+    /// #         }
+    /// #     }
+    /// # }
+    /// #
+    /// let store = Store::builder()
+    ///     .supervise(Supervision::restore())
+    ///     .with_initial(State::default());
+    ///
+    /// store.send(Action::Increment);
+    /// store.send(Action::Panic); // its changes are discarded
+    /// store.send(Action::Increment);
+    ///
+    /// assert_eq!(store.with_state(|state| state.n), 2);
+    /// assert!(matches!(store.health(), Health::Recovered { panics: 1, .. }));
+    /// # store.into_inner();
+    /// ```
+    pub fn supervise(mut self, supervision: Supervision<State>) -> Self {
+        self.supervision = supervision;
+        self
+    }
+
+    /// Calls `f` with the message of every panic in the `Store`’s [`Reducer`]; whatever
+    /// its [`Supervision`] policy.
+    ///
+    /// `f` is called on the `Store`’s runtime thread.
+    pub fn on_panic<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&str) + Send + 'static,
+    {
+        self.on_panic = Some(Box::new(f));
+        self
+    }
+
+    /// Records each action in its `reduce` span by its whole [`Debug`] output, fields and
+    /// all; rather than by just the name of its variant.
+    ///
+    /// Requires the `tracing` feature.
+    #[cfg(feature = "tracing")]
+    pub fn debug_spans(mut self) -> Self
+    where
+        <State as Reducer>::Action: std::fmt::Debug,
+    {
+        self.tracer = Tracer::describing(|action| format!("{action:?}"));
+        self
+    }
+
+    /// Runs the `Store`’s timers, and its [`Scheduler::now`], by `clock`; rather than by
+    /// the wall clock.
+    ///
+    /// [`Scheduler::now`]: crate::effects::Scheduler
+    pub fn clock(mut self, clock: Clock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Creates the `Store` with `state` as its initial state.
+    ///
+    /// See: [`Store::with_initial`]
+    pub fn with_initial(self, state: State) -> Store<State>
+    where
+        State: Send + 'static,
+        <State as Reducer>::Action: Send,
+        <State as Reducer>::Output: Send + From<State>,
+    {
+        Store::runtime(|| state, self)
+    }
+
+    /// Creates the `Store` with its initial state generated by a function.
+    ///
+    /// See: [`Store::new`]
+    pub fn build<F>(self, with: F) -> Store<State>
+    where
+        F: (FnOnce() -> State) + Send + 'static,
+        State: 'static,
+        <State as Reducer>::Action: Send + 'static,
+        <State as Reducer>::Output: Send + From<State> + 'static,
+    {
+        Store::runtime(with, self)
+    }
+}
@@ -0,0 +1,253 @@
+//! What caused each message sent to a `Store`.
+//!
+//! Every action carries the [`CorrelationId`] of the send, from outside of the `Store`’s
+//! runtime, that it ultimately resulted from. The actions sent through [`Effects::action`],
+//! by the [`Task`]s that it starts, and by its [`Scheduler`] delays, all inherit the id of
+//! the action being reduced when they were created.
+//!
+//! With the `tracing` feature, each action is also reduced within a `reduce` span whose
+//! parent is the span that caused it to be sent: the `reduce` span of the action that sent
+//! it through `Effects::action`, the `task` span of the `Task` that sent it, or whichever
+//! span was current when it was sent to the `Store`. Together they form the causal tree of
+//! a `Store`’s actions.
+//!
+//! [`Effects::action`]: crate::effects::Effects::action
+//! [`Scheduler`]: crate::effects::Scheduler
+//! [`Task`]: crate::Task
+
+use std::fmt::{Display, Formatter};
+use std::future::{poll_fn, Future};
+use std::marker::PhantomData;
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::dependencies::{with_dependency, Dependency};
+
+/// Identifies the [`Store::send`][`crate::Store::send`] that an action resulted from;
+/// however many effects, futures or delays later.
+///
+/// It is available to a [`Reducer`][`crate::Reducer`], and its [`Middleware`], as a
+/// [`Dependency`] while it is reducing an action.
+///
+/// ```rust
+/// # use composable::*;
+/// # use composable::dependencies::Dependency;
+/// # #[derive(Default)]
+/// # struct State { origins: Vec<CorrelationId> }
+/// #
+/// # #[derive(Clone, Debug, PartialEq)]
+/// # enum Action { Clicked, Saved }
+/// #
+/// # impl Reducer for State {
+/// #     type Action = Action;
+/// #     type Output = Self;
+/// #
+/// #     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+/// let origin = Dependency::<CorrelationId>::new();
+/// #         self.origins.extend(origin.copied());
+/// #
+/// #         if let Action::Clicked = action {
+/// #             send.action(Action::Saved);
+/// #         }
+/// #     }
+/// # }
+/// #
+/// # let store = Store::<State>::default();
+/// # store.send(Action::Clicked);
+/// # let origins = store.into_inner().origins;
+/// # assert_eq!(origins.len(), 2);
+/// assert_eq!(origins[0], origins[1]); // `Saved` has the id of the `Clicked` that sent it
+/// ```
+///
+/// [`Middleware`]: crate::Middleware
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CorrelationId(NonZeroU64);
+
+impl CorrelationId {
+    /// A new id; distinct from every other in the process.
+    fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+
+        let id = NEXT.fetch_add(1, Ordering::Relaxed);
+        CorrelationId(NonZeroU64::new(id).expect("CorrelationId overflow"))
+    }
+
+    /// The id as a number; for logging, or for keying whatever tracks a correlation
+    /// outside of the process.
+    ///
+    /// Ids are never zero, and are only unique within the process that created them.
+    pub fn get(self) -> u64 {
+        self.0.get()
+    }
+}
+
+impl Display for CorrelationId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// The correlation, and the span, that were current when a message was sent.
+#[derive(Clone)]
+pub struct Cause {
+    correlation: CorrelationId,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl Cause {
+    /// The current correlation, if there is one, or else a new one.
+    pub(crate) fn current() -> Self {
+        Cause {
+            correlation: Dependency::<CorrelationId>::new()
+                .copied()
+                .unwrap_or_else(CorrelationId::next),
+            #[cfg(feature = "tracing")]
+            span: tracing::Span::current(),
+        }
+    }
+
+    /// Calls `f` within the span; with the correlation as a [`Dependency`].
+    pub(crate) fn in_scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        #[cfg(feature = "tracing")]
+        let f = || self.span.in_scope(f);
+
+        with_dependency(self.correlation, f)
+    }
+
+    /// Polls `future` within a `task` span, a child of the current span, and with the
+    /// current correlation.
+    pub(crate) fn task<F: Future>(future: F) -> impl Future<Output = F::Output> {
+        let cause = Cause {
+            #[cfg(feature = "tracing")]
+            span: tracing::debug_span!("task"),
+            ..Cause::current()
+        };
+
+        let mut future = Box::pin(future);
+        poll_fn(move |cx| cause.in_scope(|| future.as_mut().poll(cx)))
+    }
+}
+
+/// The bound that the `tracing` feature places on a `Store`’s actions.
+///
+/// With the feature, each action is recorded in its `reduce` span by the name of its
+/// variant, taken from its [`Debug`][`std::fmt::Debug`] output; so actions must implement
+/// `Debug`. Without it, every type is `Traced`.
+#[cfg(feature = "tracing")]
+pub trait Traced: std::fmt::Debug {}
+
+#[cfg(feature = "tracing")]
+impl<T: std::fmt::Debug> Traced for T {}
+
+/// The bound that the `tracing` feature places on a `Store`’s actions.
+///
+/// With the feature, each action is recorded in its `reduce` span by the name of its
+/// variant, taken from its `Debug` output; so actions must implement `Debug`. Without
+/// it, as here, every type is `Traced`.
+#[cfg(not(feature = "tracing"))]
+pub trait Traced {}
+
+#[cfg(not(feature = "tracing"))]
+impl<T> Traced for T {}
+
+/// Opens the `reduce` spans for a `Store`’s actions.
+pub(crate) struct Tracer<Action> {
+    #[cfg(feature = "tracing")]
+    describe: fn(&Action) -> String,
+    marker: PhantomData<fn(&Action)>,
+}
+
+impl<Action: Traced> Default for Tracer<Action> {
+    fn default() -> Self {
+        Tracer {
+            #[cfg(feature = "tracing")]
+            describe: variant,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Action> Tracer<Action> {
+    /// A `Tracer` that records each action as `describe` formats it.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn describing(describe: fn(&Action) -> String) -> Self {
+        Tracer {
+            describe,
+            marker: PhantomData,
+        }
+    }
+
+    /// The span to reduce `action` within; a child of its `cause`.
+    ///
+    /// The action is recorded by its whole [`Debug`] output, if the `Store` was built with
+    /// [`StoreBuilder::debug_spans`], or else by the name of its variant.
+    ///
+    /// [`StoreBuilder::debug_spans`]: crate::StoreBuilder::debug_spans
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn reducing(&self, action: &Action, cause: &Cause) -> Cause {
+        #[cfg(feature = "tracing")]
+        return Cause {
+            correlation: cause.correlation,
+            span: tracing::debug_span!(
+                parent: &cause.span,
+                "reduce",
+                action = %(self.describe)(action),
+            ),
+        };
+
+        #[cfg(not(feature = "tracing"))]
+        cause.clone()
+    }
+}
+
+/// The name of `action`’s variant: its [`Debug`] output up to the first of its fields.
+///
+/// The fields themselves are never formatted; however large they may be.
+#[cfg(feature = "tracing")]
+fn variant<Action: std::fmt::Debug>(action: &Action) -> String {
+    struct Name(String);
+
+    impl std::fmt::Write for Name {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            let end = s
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+                .unwrap_or(s.len());
+            self.0.push_str(&s[..end]);
+
+            match end < s.len() {
+                true => Err(std::fmt::Error), // the name is complete; stop formatting
+                false => Ok(()),
+            }
+        }
+    }
+
+    let mut name = Name(String::new());
+    let _ = std::fmt::write(&mut name, format_args!("{action:?}"));
+    name.0
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::variant;
+
+    #[allow(dead_code)]
+    #[derive(Debug)]
+    enum Action {
+        Unit,
+        Tuple(Vec<u8>),
+        Struct { text: String },
+    }
+
+    #[test]
+    fn test_variant() {
+        assert_eq!(variant(&Action::Unit), "Unit");
+        assert_eq!(variant(&Action::Tuple(vec![1, 2, 3])), "Tuple");
+        assert_eq!(
+            variant(&Action::Struct {
+                text: "text".into()
+            }),
+            "Struct"
+        );
+    }
+}
@@ -1,72 +1,258 @@
 use std::collections::VecDeque;
-use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, Weak};
 use std::task::{Context, Poll, Waker};
-use std::{mem::swap, pin::Pin};
 
+use crossbeam_queue::SegQueue;
+use futures::task::AtomicWaker;
 use futures::Stream;
-use pin_project::pin_project;
 
+use crate::store::cause::Cause;
+
+/// The urgency with which an action sent to a [`Store`][`crate::Store`] is reduced.
+///
+/// Between action batches, the `Store` reduces any `High` priority actions that it has
+/// received before any `Normal` ones, and those before any `Low` ones. Actions of the
+/// same priority are reduced in the order they were sent.
+///
+/// See: [`Store::send_with`][`crate::Store::send_with`] and
+/// [`Effects::with_priority`][`crate::effects::Effects::with_priority`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Such as progress updates, or other bulk work.
+    Low,
+    #[default]
+    Normal,
+    /// Such as user input, or redraws.
+    High,
+}
+
+impl Priority {
+    const LANES: usize = 3;
+
+    /// The index of the lane for this priority; highest priority first.
+    fn lane(self) -> usize {
+        match self {
+            Priority::High => 0,
+            Priority::Normal => 1,
+            Priority::Low => 2,
+        }
+    }
+}
+
+/// The queue of a bounded channel, which must be locked to keep its capacity exact.
 struct Shared<T> {
-    queue: VecDeque<T>,
-    waker: Option<Waker>,
+    lanes: [VecDeque<Sent<T>>; Priority::LANES],
+    bounds: Bounds<T>,
+    blocked: Vec<Waker>, // `send_async`s waiting for space in the queue
+    dropped: usize,
+    closed: bool,
+}
+
+impl<T> Shared<T> {
+    fn len(&self) -> usize {
+        self.lanes.iter().map(VecDeque::len).sum()
+    }
+
+    fn is_full(&self) -> bool {
+        self.len() >= self.bounds.capacity
+    }
+
+    /// The `Overflow` policy to apply to `value`; if the queue is full and it applies.
+    fn overflow(&self, value: &T) -> Option<&Overflow<T>> {
+        let applies = self.is_full() && (self.bounds.applies)(value);
+        applies.then_some(&self.bounds.overflow)
+    }
+}
+
+/// The capacity of a bounded channel.
+pub(crate) struct Bounds<T> {
+    pub(crate) capacity: usize,
+    pub(crate) overflow: Overflow<T>,
+    /// Values that this returns `false` for are never blocked or dropped.
+    pub(crate) applies: fn(&T) -> bool,
+}
+
+/// What to do with an action sent to a [`Store`][`crate::Store`] that is already
+/// holding as many unprocessed actions as it has capacity for.
+///
+/// See: [`StoreBuilder::bounded`][`crate::StoreBuilder::bounded`]
+pub enum Overflow<T> {
+    /// The sending thread is blocked until there is space for the action.
+    ///
+    /// [`Task`][`crate::Task`]s are suspended, rather than blocked, as they run on the
+    /// same thread that processes the actions.
+    Block,
+    /// The oldest unprocessed action is dropped to make room for the new one.
+    ///
+    /// Lower [`Priority`] actions are dropped before higher priority ones.
+    DropOldest,
+    /// The new action is dropped.
+    DropNewest,
+    /// The newest unprocessed action, of the same [`Priority`], that the function says is
+    /// the same as the new one is dropped; and the new action is queued behind the others,
+    /// in the order that it was sent. Otherwise the new action is dropped.
+    ///
+    /// See: [`coalesce_by_key`][`Overflow::coalesce_by_key`]
+    Coalesce(Same<T>),
+}
+
+/// Whether two actions are the same, for [`Overflow::Coalesce`].
+type Same<T> = Box<dyn Fn(&T, &T) -> bool + Send + Sync>;
+
+impl<T> Overflow<T> {
+    /// Coalesces actions whose `key`s are equal.
+    ///
+    /// For example, to keep only the latest of any mouse moves
+    ///
+    /// ```rust
+    /// # use composable::*;
+    /// # #[derive(Default)]
+    /// # struct State;
+    /// #
+    /// # #[derive(Clone, Debug, PartialEq)]
+    /// # enum Action { MouseMoved(f32, f32), Clicked }
+    /// #
+    /// # impl Reducer for State {
+    /// #     type Action = Action;
+    /// #     type Output = Self;
+    /// #
+    /// #     fn reduce(&mut self, _action: Action, _send: impl Effects<Action>) {}
+    /// # }
+    /// #
+    /// let overflow = Overflow::coalesce_by_key(|action| std::mem::discriminant(action));
+    /// # let store = Store::<State>::builder().bounded(64, overflow).with_initial(State);
+    /// # store.send(Action::MouseMoved(0.0, 0.0));
+    /// # store.send(Action::Clicked);
+    /// # store.into_inner();
+    /// ```
+    pub fn coalesce_by_key<K, F>(key: F) -> Self
+    where
+        K: PartialEq,
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        Overflow::Coalesce(Box::new(move |lhs, rhs| key(lhs) == key(rhs)))
+    }
+}
+
+/// A value, and the span that was current when it was sent.
+type Sent<T> = (T, Cause);
+
+struct Channel<T> {
+    unbounded: [SegQueue<Sent<T>>; Priority::LANES], // lock-free; so that producer threads do not contend
+    bounded: Option<Bounded<T>>,
+    receiver: AtomicWaker,
+    /// Counted separately, rather than using `Arc::strong_count`, as a `Sender`
+    /// wakes the `Receiver` in its `drop` — before its `Arc` has been released.
+    senders: AtomicUsize,
+    /// Set once the `Receiver` is dropped; any values sent after that are dropped too.
+    closed: AtomicBool,
+}
+
+struct Bounded<T> {
+    shared: Mutex<Shared<T>>,
+    space: Condvar, // signalled when a full queue has room again
+}
+
+impl<T> Bounded<T> {
+    fn lock(&self) -> MutexGuard<'_, Shared<T>> {
+        self.shared.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Wakes all of the senders waiting for space.
+    fn make_space(&self, mut shared: MutexGuard<'_, Shared<T>>) {
+        let blocked = std::mem::take(&mut shared.blocked);
+        drop(shared);
+
+        self.space.notify_all();
+        blocked.into_iter().for_each(Waker::wake);
+    }
 }
 
-impl<T> Default for Shared<T> {
-    fn default() -> Self {
-        Shared {
-            queue: Default::default(),
-            waker: Default::default(),
+impl<T> Channel<T> {
+    fn len(&self) -> usize {
+        match &self.bounded {
+            None => self.unbounded.iter().map(SegQueue::len).sum(),
+            Some(bounded) => bounded.lock().len(),
         }
     }
+
+    fn pop(&self) -> Option<Sent<T>> {
+        let Some(bounded) = &self.bounded else {
+            return self.unbounded.iter().find_map(SegQueue::pop);
+        };
+
+        let mut shared = bounded.lock();
+        let was_full = shared.is_full();
+        let value = shared.lanes.iter_mut().find_map(VecDeque::pop_front);
+
+        if value.is_some() && was_full {
+            bounded.make_space(shared);
+        }
+
+        value
+    }
 }
 
-#[derive(Default)]
-#[pin_project] // See: https://blog.adamchalmers.com/pin-unpin/
 pub struct Receiver<T> {
-    shared: Arc<Mutex<Shared<T>>>,
-    buffer: VecDeque<T>,
+    shared: Arc<Channel<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// The number of values waiting to be received.
+    pub(crate) fn len(&self) -> usize {
+        self.shared.len()
+    }
 }
 
 impl<T> Stream for Receiver<T> {
-    type Item = T;
+    type Item = Sent<T>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let inner = &mut self.project();
+        let shared = &self.shared;
 
-        if let Some(value) = inner.buffer.pop_front() {
+        if let Some(value) = shared.pop() {
             return Poll::Ready(Some(value));
         }
 
-        let mut shared = inner.shared.lock().unwrap_or_else(|err| err.into_inner());
-        let external = &mut shared.queue;
+        // registered _before_ checking again, so that no send can be missed
+        shared.receiver.register(cx.waker());
+        let closed = shared.senders.load(Ordering::Acquire) == 0;
 
-        match external.pop_front() {
-            Some(value) => {
-                // move all other pending values (if any) into the (un-Mutex’d) internal buffer
-                swap(external, &mut inner.buffer);
-                Poll::Ready(Some(value))
-            }
-            None if Arc::strong_count(&inner.shared) == 1 => {
-                Poll::Ready(None) // no receivers remaining
-            }
-            None => {
-                match shared.waker.as_mut() {
-                    None => shared.waker = Some(cx.waker().clone()),
-                    Some(waker) => waker.clone_from(cx.waker()),
-                };
+        match shared.pop() {
+            Some(value) => Poll::Ready(Some(value)),
+            None if closed => Poll::Ready(None), // no senders remaining
+            None => Poll::Pending,
+        }
+    }
+}
 
-                Poll::Pending
-            }
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::SeqCst);
+
+        if let Some(bounded) = &self.shared.bounded {
+            let mut shared = bounded.lock();
+            shared.closed = true; // no blocked sender should wait for it
+
+            bounded.make_space(shared);
         }
+
+        // such as `with_state`’s replies, which would otherwise wait forever
+        while self.shared.pop().is_some() {}
     }
 }
 
 pub struct Sender<T> {
-    shared: Arc<Mutex<Shared<T>>>,
+    shared: Arc<Channel<T>>,
 }
 
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+
         Sender {
             shared: self.shared.clone(),
         }
@@ -75,13 +261,46 @@ impl<T> Clone for Sender<T> {
 
 impl<T> Drop for Sender<T> {
     fn drop(&mut self) {
-        self.wake_after(|_| { /* drop */ })
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.receiver.wake()
+        }
     }
 }
 
 impl<T> Sender<T> {
+    /// Sends `value`; applying the channel’s [`Overflow`] policy if it is full.
     pub fn send(&self, value: T) {
-        self.wake_after(move |mut shared| shared.queue.push_back(value))
+        self.push(Priority::Normal, value, true)
+    }
+
+    /// Sends `value` with `priority`; applying the channel’s [`Overflow`] policy if it is full.
+    pub fn send_with(&self, priority: Priority, value: T) {
+        self.push(priority, value, true)
+    }
+
+    /// Sends `value` without ever blocking the current thread; it may exceed the channel’s
+    /// capacity, rather than [`Block`][`Overflow::Block`], but is otherwise subject to its
+    /// `Overflow` policy.
+    pub fn send_nonblocking(&self, value: T) {
+        self.push(Priority::Normal, value, false)
+    }
+
+    /// Sends `value` with `priority` once there is room for it.
+    ///
+    /// Unlike [`send_with`][`Sender::send_with`], this does not block the current thread
+    /// when the channel’s `Overflow` policy is to [`Block`][`Overflow::Block`].
+    pub async fn send_async(&self, priority: Priority, value: T) {
+        if self.shared.bounded.is_some() {
+            poll_fn(|cx| self.poll_ready(cx, &value)).await;
+        }
+
+        self.push(priority, value, false)
+    }
+
+    /// The number of values dropped, or coalesced, due to the channel being full.
+    pub fn dropped(&self) -> usize {
+        let bounded = self.shared.bounded.as_ref();
+        bounded.map_or(0, |bounded| bounded.lock().dropped)
     }
 
     pub fn downgrade(&self) -> WeakSender<T> {
@@ -90,28 +309,99 @@ impl<T> Sender<T> {
         }
     }
 
-    /// Perform some work and then, if a `Receiver` was waiting, wake it.
-    ///
-    /// Note that the [`Waker`] will only ever be called once for each time it
-    /// has entered the [`Poll::Pending`] state. Regardless of how many times
-    /// `wake_after` is called.
-    fn wake_after<F: FnOnce(MutexGuard<Shared<T>>)>(&self, f: F) {
-        let mut shared = self
-            .shared
-            .lock() //
-            .unwrap_or_else(|err| err.into_inner());
-
-        let waker = shared.waker.take(); // there are no “extra” wakes
-        f(shared);
-
-        if let Some(waker) = waker {
-            waker.wake() // wake _after_ the `MutexGuard` has been dropped by `f(…)`
+    fn poll_ready(&self, cx: &mut Context<'_>, value: &T) -> Poll<()> {
+        let Some(bounded) = &self.shared.bounded else {
+            return Poll::Ready(());
+        };
+
+        let mut shared = bounded.lock();
+
+        match shared.overflow(value) {
+            Some(Overflow::Block) if !shared.closed => {
+                shared.blocked.push(cx.waker().clone());
+                Poll::Pending
+            }
+            _ => Poll::Ready(()),
         }
     }
+
+    fn push(&self, priority: Priority, value: T, block: bool) {
+        let value = (value, Cause::current());
+
+        match &self.shared.bounded {
+            None => self.shared.unbounded[priority.lane()].push(value),
+            Some(bounded) => {
+                if !Self::push_bounded(bounded, priority.lane(), value, block) {
+                    return;
+                }
+            }
+        }
+
+        if self.shared.closed.load(Ordering::SeqCst) {
+            while self.shared.pop().is_some() {} // the `Receiver` will never see it
+        }
+
+        self.shared.receiver.wake()
+    }
+
+    /// Returns `false` if `value` was dropped, rather than queued.
+    fn push_bounded(bounded: &Bounded<T>, lane: usize, value: Sent<T>, block: bool) -> bool {
+        let mut shared = bounded.lock();
+
+        match shared.overflow(&value.0) {
+            None => {}
+            Some(Overflow::Block) if !block => {} // may exceed the capacity; rather than deadlock
+            Some(Overflow::Block) => {
+                shared = bounded
+                    .space
+                    .wait_while(shared, |shared| shared.is_full() && !shared.closed)
+                    .unwrap_or_else(|err| err.into_inner());
+            }
+            Some(Overflow::DropNewest) => {
+                shared.dropped += 1;
+                return false;
+            }
+            Some(Overflow::DropOldest) => {
+                let Shared { lanes, bounds, .. } = &mut *shared;
+                let oldest = lanes.iter_mut().rev().find_map(|queue| {
+                    let index = queue
+                        .iter()
+                        .position(|(queued, _)| (bounds.applies)(queued))?;
+                    queue.remove(index)
+                });
+
+                if oldest.is_some() {
+                    shared.dropped += 1;
+                }
+            }
+            Some(Overflow::Coalesce(_)) => {
+                let Shared { lanes, bounds, .. } = &mut *shared;
+                let queue = &mut lanes[lane];
+                let Overflow::Coalesce(same) = &bounds.overflow else {
+                    unreachable!()
+                };
+
+                let applies = bounds.applies;
+                let index = queue
+                    .iter()
+                    .rposition(|(queued, _)| applies(queued) && same(queued, &value.0));
+
+                shared.dropped += 1; // either the queued action, or `value`
+                let Some(index) = index else {
+                    return false;
+                };
+
+                shared.lanes[lane].remove(index); // superseded by `value`; queued below
+            }
+        }
+
+        shared.lanes[lane].push_back(value);
+        true
+    }
 }
 
 pub struct WeakSender<T> {
-    shared: Weak<Mutex<Shared<T>>>,
+    shared: Weak<Channel<T>>,
 }
 
 impl<T> Clone for WeakSender<T> {
@@ -124,29 +414,48 @@ impl<T> Clone for WeakSender<T> {
 
 impl<T> WeakSender<T> {
     pub fn upgrade(&self) -> Option<Sender<T>> {
-        self.shared
-            .upgrade() //
-            .map(|shared| Sender { shared })
+        let shared = self.shared.upgrade()?;
+        shared.senders.fetch_add(1, Ordering::Relaxed);
+
+        Some(Sender { shared })
     }
 }
 
 pub struct WeakReceiver<T> {
-    shared: Weak<Mutex<Shared<T>>>,
+    shared: Weak<Channel<T>>,
 }
 
 impl<T> WeakReceiver<T> {
     pub fn upgrade(self) -> Option<Receiver<T>> {
         self.shared
             .upgrade() //
-            .map(|shared| Receiver {
-                shared,
-                buffer: Default::default(),
-            })
+            .map(|shared| Receiver { shared })
     }
 }
 
 pub fn channel<T>() -> (Sender<T>, WeakReceiver<T>) {
-    let shared = Default::default();
+    bounded(None)
+}
+
+pub fn bounded<T>(bounds: Option<Bounds<T>>) -> (Sender<T>, WeakReceiver<T>) {
+    let bounded = bounds.map(|bounds| Bounded {
+        shared: Mutex::new(Shared {
+            lanes: Default::default(),
+            bounds,
+            blocked: Vec::new(),
+            dropped: 0,
+            closed: false,
+        }),
+        space: Condvar::new(),
+    });
+
+    let shared = Arc::new(Channel {
+        unbounded: Default::default(),
+        bounded,
+        receiver: AtomicWaker::new(),
+        senders: AtomicUsize::new(1),
+        closed: AtomicBool::new(false),
+    });
 
     let recv = WeakReceiver {
         shared: Arc::downgrade(&shared),
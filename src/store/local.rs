@@ -0,0 +1,350 @@
+use std::sync::Arc;
+use std::task::Context;
+
+use futures::executor::LocalPool;
+use futures::future::RemoteHandle;
+use futures::task::{AtomicWaker, LocalSpawnExt};
+
+use crate::dependencies::with_dependency;
+use crate::effects::{notifying, Executor};
+use crate::reducer::Reducer;
+use crate::store::cause::Traced;
+use crate::store::channel::{channel, Sender};
+use crate::store::middleware::Added;
+use crate::store::{runtime::run, Control, Middleware, Priority};
+
+/// A `Store` that runs on the current thread, rather than on one of its own.
+///
+/// A `LocalStore` does nothing until it is driven by its owner; usually from within an
+/// existing event loop. Each call to [`run_until_stalled`] reduces the actions that have
+/// been sent, and runs the `Store`’s tasks, until no more progress can be made.
+///
+/// As it never leaves the current thread, neither the `State`, its `Action`s or its
+/// `Output` need to be [`Send`].
+///
+/// ```rust
+/// # use std::rc::Rc;
+/// # use std::cell::Cell;
+/// # use composable::*;
+/// #
+/// #[derive(Default)]
+/// struct State {
+///     n: Rc<Cell<usize>>, // Rc<Cell<…>> is not Send
+/// }
+///
+/// #[derive(Debug)]
+/// enum Action {
+///     Increment,
+/// }
+///
+/// impl Reducer for State {
+///     type Action = Action;
+///     type Output = Self;
+///
+///     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+///         match action {
+///             Action::Increment => self.n.set(self.n.get() + 1),
+///         }
+///     }
+/// }
+///
+/// let n = Rc::new(Cell::new(0));
+/// let mut store = LocalStore::with_initial(State { n: n.clone() });
+///
+/// store.send(Action::Increment);
+/// assert_eq!(n.get(), 0); // nothing happens until the store is run
+///
+/// store.run_until_stalled();
+/// assert_eq!(n.get(), 1);
+/// ```
+///
+/// [`run_until_stalled`]: LocalStore::run_until_stalled
+pub struct LocalStore<State: Reducer> {
+    sender: Sender<Result<<State as Reducer>::Action, Control>>,
+    executor: Executor<Result<<State as Reducer>::Action, Control>>,
+    handle: RemoteHandle<State>,
+    pool: LocalPool,
+    notify: Arc<AtomicWaker>,
+    middleware: Added<State>,
+}
+
+impl<State: Reducer> LocalStore<State>
+where
+    State: 'static,
+    <State as Reducer>::Action: Traced + 'static,
+{
+    /// Creates a new `LocalStore` with `state` as its initial state.
+    pub fn with_initial(state: State) -> Self {
+        let (sender, receiver) = channel();
+        let receiver = receiver.upgrade().unwrap();
+
+        let pool = LocalPool::new();
+        let executor = Executor::new(pool.spawner(), sender.downgrade());
+
+        let notify = Arc::new(AtomicWaker::new());
+        executor.tasks.notify(notify.clone());
+
+        let middleware = Added::default();
+        let running = run(
+            state,
+            receiver,
+            executor.tasks.clone(),
+            middleware.clone(),
+            Default::default(),
+            Default::default(),
+        );
+
+        let handle = pool
+            .spawner()
+            .spawn_local_with_handle(notifying(running, notify.clone()))
+            .expect("LocalStore runtime");
+
+        LocalStore {
+            sender,
+            executor,
+            handle,
+            pool,
+            notify,
+            middleware,
+        }
+    }
+
+    /// Creates a new `LocalStore` with its initial state generated by a function.
+    pub fn new<F>(with: F) -> Self
+    where
+        F: FnOnce() -> State,
+    {
+        Self::with_initial(with())
+    }
+
+    /// Sends `action` to the `Store`’s [`Reducer`][`crate::Reducer`].
+    ///
+    /// The action is not reduced until the next call to
+    /// [`run_until_stalled`][`LocalStore::run_until_stalled`].
+    pub fn send(&self, action: impl Into<<State as Reducer>::Action>) {
+        self.sender.send(Ok(action.into()))
+    }
+
+    /// Calls the `LocalStore`’s [`Reducer`] with `action`; ahead of any lower
+    /// [`Priority`] actions that are still waiting to be reduced.
+    ///
+    /// See: [`Store::send_with`][`crate::Store::send_with`]
+    pub fn send_with(&self, priority: Priority, action: impl Into<<State as Reducer>::Action>) {
+        self.sender.send_with(priority, Ok(action.into()))
+    }
+
+    /// Adds a [`Middleware`] to be called around every action that the `Store` reduces
+    /// from now on.
+    ///
+    /// Unlike [`Store::add_middleware`][`crate::Store::add_middleware`], the `Middleware`
+    /// need not be [`Send`].
+    pub fn add_middleware<M>(&self, middleware: M)
+    where
+        M: Middleware<State> + 'static,
+    {
+        self.middleware.borrow_mut().push(Box::new(middleware));
+    }
+
+    /// Reduces all of the actions sent so far, and runs the `Store`’s tasks,
+    /// until no more progress can be made without waiting.
+    ///
+    /// Tasks that are waiting on other threads (or timers) will continue
+    /// on a later call; once they are ready.
+    pub fn run_until_stalled(&mut self) {
+        let executor = self.executor.clone();
+        with_dependency(executor, || self.pool.run_until_stalled())
+    }
+
+    /// Like [`run_until_stalled`], but also arranges for the waker of `cx` to be woken
+    /// once the `Store` can make progress again; such as when a timer fires, or a task
+    /// receives a result from another thread.
+    ///
+    /// An event loop can then sleep until it is woken; by a [`Waker`] that, for example,
+    /// sends an event through a `winit` `EventLoopProxy`.
+    ///
+    /// [`run_until_stalled`]: LocalStore::run_until_stalled
+    /// [`Waker`]: std::task::Waker
+    pub fn poll_until_stalled(&mut self, cx: &mut Context<'_>) {
+        self.notify.register(cx.waker());
+        self.run_until_stalled();
+    }
+
+    /// Stops the `Store`’s runtime and returns its current `state` value.
+    ///
+    /// Like [`Store::into_inner`][`crate::Store::into_inner`], this blocks the current
+    /// thread until all of the `Store`’s [`Task`][`crate::Task`]s have finished; other
+    /// than detached ones, which are cancelled.
+    pub fn into_inner(self) -> <State as Reducer>::Output
+    where
+        <State as Reducer>::Output: From<State>,
+    {
+        let LocalStore {
+            sender,
+            executor,
+            handle,
+            mut pool,
+            ..
+        } = self;
+
+        sender.send(Err(Control::Shutdown));
+        drop(sender); // ends the runtime’s (outer) while-let once its tasks are done
+        with_dependency(executor, || pool.run_until(handle)).into()
+    }
+}
+
+impl<State: Reducer> Default for LocalStore<State>
+where
+    State: Default + 'static,
+    <State as Reducer>::Action: Traced + 'static,
+{
+    /// Creates a new `LocalStore` with a default initial state.
+    fn default() -> Self {
+        Self::with_initial(State::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use futures::channel::oneshot;
+    use futures::task::{waker, ArcWake};
+
+    use crate::store::runtime::tests::{Action, Action::*, State};
+    use crate::Effects;
+
+    use super::*;
+
+    #[test]
+    fn test_tasks_run_until_stalled() {
+        let mut store = LocalStore::<State>::default();
+
+        store.send(External('1'));
+        store.send(External('F')); // `f` is sent from a future
+        store.send(External('2'));
+        store.run_until_stalled();
+
+        let values = store.into_inner().characters.lock().unwrap().clone();
+        assert_eq!(values, vec!['1', 'A', 'B', 'C', 'D', 'F', '2', 'f']);
+    }
+
+    #[test]
+    fn test_poll_until_stalled_wakes() {
+        /// Receives a `char` from another thread.
+        struct Waiting(Option<oneshot::Receiver<char>>, Vec<char>);
+
+        #[derive(Debug)]
+        enum Wait {
+            Start,
+            Received(char),
+        }
+
+        impl Reducer for Waiting {
+            type Action = Wait;
+            type Output = Vec<char>;
+
+            fn reduce(&mut self, action: Wait, send: impl Effects<Wait>) {
+                match action {
+                    Wait::Start => {
+                        let receiver = self.0.take().unwrap();
+                        send.future(async move { receiver.await.ok().map(Wait::Received) });
+                    }
+                    Wait::Received(ch) => self.1.push(ch),
+                }
+            }
+        }
+
+        impl From<Waiting> for Vec<char> {
+            fn from(waiting: Waiting) -> Self {
+                waiting.1
+            }
+        }
+
+        #[derive(Default)]
+        struct Woken(AtomicBool);
+
+        impl ArcWake for Woken {
+            fn wake_by_ref(arc_self: &Arc<Self>) {
+                arc_self.0.store(true, Ordering::Release);
+            }
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        let mut store = LocalStore::with_initial(Waiting(Some(receiver), Vec::new()));
+
+        let woken = Arc::new(Woken::default());
+        let waker = waker(woken.clone());
+
+        store.send(Wait::Start);
+        store.poll_until_stalled(&mut Context::from_waker(&waker));
+        woken.0.store(false, Ordering::Release); // ignoring any wakes while it was running
+
+        std::thread::spawn(move || sender.send('x').unwrap())
+            .join()
+            .unwrap();
+        assert!(woken.0.load(Ordering::Acquire)); // without the `LocalStore` being polled
+
+        store.run_until_stalled();
+        assert_eq!(store.into_inner(), ['x']);
+    }
+
+    /// Logs each `before` and `after` call; through an `Rc`, so it is not `Send`.
+    struct Log(Rc<RefCell<Vec<String>>>, &'static str, Option<char>);
+
+    impl Middleware<State> for Log {
+        fn before(&mut self, action: &Action, _state: &State) -> bool {
+            self.0
+                .borrow_mut()
+                .push(format!("{} before {action:?}", self.1));
+            !matches!((action, self.2), (External(ch), Some(dropped)) if *ch == dropped)
+        }
+
+        fn after(&mut self, _state: &State) {
+            self.0.borrow_mut().push(format!("{} after", self.1));
+        }
+    }
+
+    #[test]
+    fn test_middleware_need_not_be_send() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut store = LocalStore::<State>::default();
+        store.add_middleware(Log(log.clone(), "outer", None));
+
+        store.send(External('a'));
+        store.run_until_stalled();
+
+        assert_eq!(*log.borrow(), ["outer before External('a')", "outer after"]);
+    }
+
+    #[test]
+    fn test_middleware_after_dropped_actions() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut store = LocalStore::<State>::default();
+        store.add_middleware(Log(log.clone(), "outer", None));
+        store.add_middleware(Log(log.clone(), "inner", Some('x')));
+
+        store.send(External('x')); // dropped by the inner layer
+        store.run_until_stalled();
+
+        #[rustfmt::skip]
+        assert_eq!(*log.borrow(), [
+            "outer before External('x')",
+            "inner before External('x')",
+            "outer after", // though it was never reduced
+        ]);
+
+        let values = store.into_inner().characters.lock().unwrap().clone();
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn test_into_inner_waits_for_tasks() {
+        let store = LocalStore::<State>::default();
+        store.send(External('F'));
+
+        let values = store.into_inner().characters.lock().unwrap().clone();
+        assert_eq!(values, vec!['F', 'f']);
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::mem::{discriminant, Discriminant};
+use std::time::Duration;
+
+/// A snapshot of how a [`Store`][`crate::Store`]’s runtime is performing.
+///
+/// See: [`Store::metrics`][`crate::Store::metrics`]
+pub struct Metrics<Action> {
+    reduced: HashMap<Discriminant<Action>, Timing>,
+    /// The number of actions (and other messages) waiting to be reduced.
+    pub queued: usize,
+    /// The most actions (and other messages) that have been waiting at once.
+    pub high_water: usize,
+    /// The number of [`Task`][`crate::Task`]s that are running; detached or not.
+    pub tasks: usize,
+    /// The number of timers, such as those started by [`Scheduler::after`], that are
+    /// waiting to fire.
+    ///
+    /// [`Scheduler::after`]: crate::effects::Scheduler::after
+    pub delays: usize,
+}
+
+impl<Action> Clone for Metrics<Action> {
+    fn clone(&self) -> Self {
+        Metrics {
+            reduced: self.reduced.clone(),
+            queued: self.queued,
+            high_water: self.high_water,
+            tasks: self.tasks,
+            delays: self.delays,
+        }
+    }
+}
+
+impl<Action> Debug for Metrics<Action> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Metrics")
+            .field("reduced", &self.reduced.values().copied().sum::<Timing>())
+            .field("queued", &self.queued)
+            .field("high_water", &self.high_water)
+            .field("tasks", &self.tasks)
+            .field("delays", &self.delays)
+            .finish()
+    }
+}
+
+impl<Action> Metrics<Action> {
+    /// How long it has taken to reduce actions of the same variant as `action`.
+    ///
+    /// ```rust
+    /// # use composable::*;
+    /// # #[derive(Default)]
+    /// # struct State;
+    /// #
+    /// # #[derive(Clone, Debug, PartialEq)]
+    /// # enum Action { Redraw, Resize }
+    /// #
+    /// # impl Reducer for State {
+    /// #     type Action = Action;
+    /// #     type Output = Self;
+    /// #
+    /// #     fn reduce(&mut self, _action: Action, _send: impl Effects<Action>) {}
+    /// # }
+    /// #
+    /// # let store = Store::<State>::default();
+    /// store.send(Action::Redraw);
+    /// store.send(Action::Resize);
+    /// store.send(Action::Redraw);
+    /// store.with_state(|_| ()); // once every action has been reduced
+    ///
+    /// let redraws = store.metrics().timing(&Action::Redraw);
+    /// assert_eq!(redraws.count, 2);
+    /// # store.into_inner();
+    /// ```
+    pub fn timing(&self, action: &Action) -> Timing {
+        self.reduced
+            .get(&discriminant(action))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// How long it has taken to reduce each variant of `Action` that has been reduced.
+    pub fn timings(&self) -> impl Iterator<Item = (Discriminant<Action>, Timing)> + '_ {
+        self.reduced.iter().map(|(kind, timing)| (*kind, *timing))
+    }
+
+    /// How long it has taken to reduce all of the actions.
+    pub fn total(&self) -> Timing {
+        self.reduced.values().copied().sum()
+    }
+}
+
+/// How long a number of calls to a [`Reducer`][`crate::Reducer`] took; including any
+/// [`Middleware`][`crate::Middleware`] around them.
+///
+/// Durations are measured by the system’s monotonic clock ([`Instant`]); even if the
+/// `Store` was built with a [`StoreBuilder::clock`].
+///
+/// [`Instant`]: std::time::Instant
+/// [`StoreBuilder::clock`]: crate::StoreBuilder::clock
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timing {
+    /// The number of actions; including any that a `Middleware` dropped before they
+    /// reached the `Reducer`.
+    pub count: u64,
+    /// The time spent on them all, in the `Reducer` and its `Middleware`.
+    pub total: Duration,
+    /// The longest time spent on any one of them, in the `Reducer` and its `Middleware`.
+    pub max: Duration,
+}
+
+impl Timing {
+    /// The mean duration; or zero if there were none.
+    pub fn mean(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            count => self.total / count.min(u32::MAX as u64) as u32,
+        }
+    }
+
+    fn add(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+}
+
+impl std::iter::Sum for Timing {
+    fn sum<I: Iterator<Item = Timing>>(iter: I) -> Self {
+        iter.fold(Timing::default(), |lhs, rhs| Timing {
+            count: lhs.count + rhs.count,
+            total: lhs.total + rhs.total,
+            max: lhs.max.max(rhs.max),
+        })
+    }
+}
+
+/// Collects the [`Metrics`] within the runtime.
+pub(crate) struct Collector<Action> {
+    metrics: Metrics<Action>,
+}
+
+impl<Action> Default for Collector<Action> {
+    fn default() -> Self {
+        Collector {
+            metrics: Metrics {
+                reduced: HashMap::new(),
+                queued: 0,
+                high_water: 0,
+                tasks: 0,
+                delays: 0,
+            },
+        }
+    }
+}
+
+impl<Action> Collector<Action> {
+    /// Called as each message is received; with the number still `queued` behind it.
+    pub(crate) fn received(&mut self, queued: usize) {
+        let high_water = &mut self.metrics.high_water;
+        *high_water = (*high_water).max(queued + 1);
+    }
+
+    pub(crate) fn reduced(&mut self, kind: Discriminant<Action>, elapsed: Duration) {
+        self.metrics.reduced.entry(kind).or_default().add(elapsed);
+    }
+
+    pub(crate) fn snapshot(&self, queued: usize, tasks: usize, delays: usize) -> Metrics<Action> {
+        Metrics {
+            queued,
+            tasks,
+            delays,
+            ..self.metrics.clone()
+        }
+    }
+}
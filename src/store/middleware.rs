@@ -0,0 +1,125 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::dependencies::with_dependency;
+use crate::{Effects, Reducer};
+
+/// `Middleware` is called around every call to a `Store`’s [`Reducer`]; for both the
+/// actions sent to the `Store` and those sent by its [`Effects`].
+///
+/// It allows the same logging, metrics or filtering to be applied to many different
+/// `Reducer`s without changing any of them.
+///
+/// ```rust
+/// # use composable::*;
+/// #
+/// # #[derive(Clone, Debug, Default, PartialEq)]
+/// # struct State {
+/// #     n: usize,
+/// # }
+/// #
+/// #[derive(Debug, PartialEq)]
+/// enum Action {
+///     Increment,
+///     Ignored,
+/// }
+///
+/// # impl Reducer for State {
+/// #     type Action = Action;
+/// #     type Output = Self;
+/// #
+/// #     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+/// #         if let Action::Increment = action {
+/// #             self.n += 1;
+/// #         }
+/// #     }
+/// # }
+/// #
+/// #[derive(Default)]
+/// struct Filter {
+///     dropped: usize,
+/// }
+///
+/// impl Middleware<State> for Filter {
+///     fn before(&mut self, action: &Action, _state: &State) -> bool {
+///         if let Action::Ignored = action {
+///             self.dropped += 1;
+///             return false;
+///         }
+///
+///         true
+///     }
+/// }
+///
+/// let mut store = TestStore::<State>::default();
+/// store.add_middleware(Filter::default());
+///
+/// store.send(Action::Increment, |state| state.n = 1);
+/// store.send(Action::Ignored, |_| {});
+/// ```
+pub trait Middleware<State: Reducer> {
+    /// Called before `action` is reduced.
+    ///
+    /// Returning `false` drops the action; it will not be reduced and no later `Middleware`
+    /// will see it. The earlier `Middleware`, which did see it, are still called
+    /// [`after`][`Middleware::after`]; with the unchanged state.
+    fn before(&mut self, action: &<State as Reducer>::Action, state: &State) -> bool {
+        let _ = (action, state);
+        true
+    }
+
+    /// Called after an action has been reduced, or dropped by a later `Middleware`; with
+    /// the resulting `state`.
+    ///
+    /// As the action was consumed by the `Reducer`, any information about it that is
+    /// needed here should be kept by [`before`][`Middleware::before`].
+    fn after(&mut self, state: &State) {
+        let _ = state;
+    }
+}
+
+/// `Middleware` handed directly to a runtime, rather than through its channel; as a
+/// `LocalStore` does, so that its `Middleware` need not be `Send`.
+pub(crate) type Added<State> = Rc<RefCell<Vec<Box<dyn Middleware<State>>>>>;
+
+/// Marks the `after` calls for an action that a layer dropped, as a [`Dependency`]; for the
+/// crate’s own `Middleware` that must tell them apart.
+///
+/// [`Dependency`]: crate::dependencies::Dependency
+pub(crate) struct Dropped;
+
+/// Reduces `action` within the `middleware`.
+///
+/// Every layer whose `before` was called is also called `after`; even if a later layer
+/// dropped the action, in which case they are called with [`Dropped`].
+pub(crate) fn reduce<State: Reducer>(
+    middleware: &mut [Box<dyn Middleware<State>>],
+    state: &mut State,
+    action: <State as Reducer>::Action,
+    effects: impl Effects<<State as Reducer>::Action>,
+) {
+    let mut entered = 0;
+    for layer in middleware.iter_mut() {
+        if !layer.before(&action, state) {
+            break;
+        }
+
+        entered += 1;
+    }
+
+    let dropped = entered < middleware.len();
+    if !dropped {
+        state.reduce(action, effects);
+    }
+
+    let mut after = || {
+        for layer in middleware[..entered].iter_mut().rev() {
+            layer.after(state);
+        }
+    };
+
+    match dropped {
+        true => with_dependency(Dropped, after),
+        false => after(),
+    }
+}
@@ -1,21 +1,100 @@
-use std::thread::{JoinHandle, Thread};
+use std::any::Any;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::panic::resume_unwind;
+use std::pin::Pin;
+use std::sync::{mpsc, Arc, Mutex};
+use std::task::{Context, Poll};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use futures::channel::{mpsc::unbounded, oneshot};
+use futures::executor::block_on;
+use futures::{Future, FutureExt, Stream};
 
 use channel::Sender;
 
-use crate::Reducer;
+use crate::{Clock, Reducer};
+use recording::{Recorder, Recording};
+
+pub use builder::StoreBuilder;
+pub use cause::{CorrelationId, Traced};
+pub use channel::{Overflow, Priority};
+pub use local::LocalStore;
+pub use metrics::{Metrics, Timing};
+pub use middleware::Middleware;
+pub use supervision::{Health, Supervision};
 
+mod bridge;
+mod builder;
+pub(crate) mod cause;
 pub(crate) mod channel;
+mod local;
+mod metrics;
+pub(crate) mod middleware;
+pub mod recording;
 mod runtime;
+mod supervision;
 
 pub(crate) mod testing;
 
 #[doc = include_str!("README.md")]
 pub struct Store<State: Reducer> {
-    sender: Sender<Result<<State as Reducer>::Action, Thread>>,
+    sender: Sender<Result<<State as Reducer>::Action, Control>>,
     handle: JoinHandle<<State as Reducer>::Output>,
+    health: Arc<Mutex<Health>>,
+    clock: Option<Clock>,
+}
+
+/// Messages, other than `Action`s, that are sent to the `Store`’s runtime.
+///
+/// They are sent through the same channel as the `Action`s so that they are
+/// processed in the order they were sent; between action batches.
+pub(crate) enum Control {
+    /// Notified once the runtime has no running tasks.
+    ///
+    /// See: [`Store::into_inner_timeout`]
+    Idle(mpsc::Sender<()>),
+    /// A `Box<dyn Middleware<State> + Send>` to add to the runtime.
+    ///
+    /// See: [`Store::add_middleware`]
+    Middleware(Box<dyn Any + Send>),
+    /// A `Record<Action>` to call with each action before it is reduced.
+    ///
+    /// See: [`Store::record`]
+    Record(Box<dyn Any + Send>),
+    /// Cancels the runtime’s running tasks; and any new ones.
+    ///
+    /// See: [`Store::shutdown_now`]
+    Cancel,
+    /// Cancels the runtime’s detached tasks once all of its other tasks have finished.
+    ///
+    /// See: [`Store::into_inner`]
+    Shutdown,
+    /// An `(Action, Ticketed)` to reduce; notifying the [`Ticket`] as it progresses.
+    ///
+    /// See: [`Store::send_and_wait`]
+    Ticket(Box<dyn Any + Send>),
+    /// A function called with the `State` after every action batch.
+    /// It is removed once it returns `false`.
+    Watch(Watcher),
+    /// A `oneshot::Sender<Metrics<Action>>` to send a snapshot of the runtime’s metrics to.
+    ///
+    /// See: [`Store::metrics`]
+    Metrics(Box<dyn Any + Send>),
 }
 
+pub(crate) type Watcher = Box<dyn FnMut(&dyn Any) -> bool + Send>;
+
 impl<State: Reducer> Store<State> {
+    /// A [`StoreBuilder`] for a `Store` with non-default settings, such as a bounded capacity.
+    pub fn builder() -> StoreBuilder<State>
+    where
+        <State as Reducer>::Action: Traced,
+    {
+        StoreBuilder::default()
+    }
+
     /// Creates a new `Store` with `state` as its initial state.
     ///
     /// If `State` is not [`Send`], then [`new`][`Store::new`] or [`default`][`Store::default`]
@@ -23,22 +102,26 @@ impl<State: Reducer> Store<State> {
     pub fn with_initial(state: State) -> Self
     where
         State: Send + 'static,
-        <State as Reducer>::Action: Send,
+        <State as Reducer>::Action: Traced + Send,
         <State as Reducer>::Output: Send + From<State>,
     {
-        Store::runtime(|| state)
+        Store::runtime(|| state, StoreBuilder::default())
     }
 
     /// Creates a new `Store` with its initial state generated by a function.
     ///
     /// Useful if `State` is not [`Send`], but the arguments used to construct it are.
+    ///
+    /// `State` must be `'static`, though; as the `Store`’s watchers, [`Middleware`] and
+    /// [`Supervision`] policy are typed by it, and are handed to its runtime thread.
     pub fn new<F>(with: F) -> Self
     where
         F: (FnOnce() -> State) + Send + 'static,
-        <State as Reducer>::Action: Send + 'static,
+        State: 'static,
+        <State as Reducer>::Action: Traced + Send + 'static,
         <State as Reducer>::Output: Send + From<State> + 'static,
     {
-        Store::runtime(with)
+        Store::runtime(with, StoreBuilder::default())
     }
 
     /// Calls the `Store`’s [`Reducer`][`crate::Reducer`] with `action`.
@@ -48,26 +131,445 @@ impl<State: Reducer> Store<State> {
         self.sender.send(Ok(action.into()))
     }
 
-    /// Stops the `Store`’s runtime and returns its current `state` value.  
+    /// Whether the `Store`’s [`Reducer`][`crate::Reducer`] has panicked; and, if so,
+    /// whether the `Store` recovered.
+    ///
+    /// See: [`StoreBuilder::supervise`]
+    pub fn health(&self) -> Health {
+        let health = self.health.lock().unwrap_or_else(|err| err.into_inner());
+        health.clone()
+    }
+
+    /// A snapshot of the `Store`’s runtime [`Metrics`]: how long its actions have taken
+    /// to reduce, how many are waiting and how many tasks and timers are running.
+    ///
+    /// The request is sent at [`Priority::High`], so it is answered ahead of any
+    /// queued actions.
+    ///
+    /// # Note
+    /// Like [`with_state`][`Store::with_state`], this blocks the calling thread until
+    /// the runtime gets to it.
+    pub fn metrics(&self) -> Metrics<<State as Reducer>::Action>
+    where
+        <State as Reducer>::Action: 'static,
+    {
+        let (sender, receiver) = oneshot::channel::<Metrics<<State as Reducer>::Action>>();
+        let request = Control::Metrics(Box::new(sender));
+        self.sender.send_with(Priority::High, Err(request));
+
+        block_on(receiver).expect("the Store’s runtime has stopped")
+    }
+
+    /// Calls the `Store`’s [`Reducer`][`crate::Reducer`] with `action`, returning a
+    /// [`Ticket`] that resolves once it — and every action that it synchronously sent
+    /// — has been reduced.
+    ///
+    /// ```rust
+    /// # use composable::*;
+    /// # use futures::executor::block_on;
+    /// # #[derive(Default)]
+    /// # struct State { n: usize }
+    /// #
+    /// # #[derive(Clone, Debug, PartialEq)]
+    /// # enum Action { Increment, Twice }
+    /// #
+    /// # impl Reducer for State {
+    /// #     type Action = Action;
+    /// #     type Output = Self;
+    /// #
+    /// #     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+    /// #         match action {
+    /// #             Action::Increment => self.n += 1,
+    /// #             Action::Twice => {
+    /// #                 send.action(Action::Increment);
+    /// #                 send.future(async { Some(Action::Increment) });
+    /// #             }
+    /// #         }
+    /// #     }
+    /// # }
+    /// #
+    /// let store = Store::<State>::default();
+    ///
+    /// block_on(store.send_and_wait(Action::Twice).tasks());
+    /// assert_eq!(store.with_state(|state| state.n), 2);
+    /// # store.into_inner();
+    /// ```
+    ///
+    /// Unlike [`send`][`Store::send`], the action is never dropped by the `Store`’s
+    /// [`Overflow`] policy; awaiting the `Ticket` is back-pressure enough.
+    pub fn send_and_wait(&self, action: impl Into<<State as Reducer>::Action>) -> Ticket
+    where
+        <State as Reducer>::Action: Send + 'static,
+    {
+        let (reduced, reduced_receiver) = oneshot::channel();
+        let (finished, finished_receiver) = oneshot::channel();
+
+        let ticketed = (action.into(), Ticketed { reduced, finished });
+        self.sender.send(Err(Control::Ticket(Box::new(ticketed))));
+
+        Ticket {
+            reduced: reduced_receiver,
+            finished: finished_receiver,
+        }
+    }
+
+    /// Calls the `Store`’s [`Reducer`][`crate::Reducer`] with `action`; ahead of any
+    /// lower [`Priority`] actions that are still waiting to be reduced.
+    ///
+    /// Actions sent with different priorities, including the [`Normal`] priority of
+    /// [`send`][`Store::send`], may be reduced in a different order than they were sent.
+    ///
+    /// [`Normal`]: Priority::Normal
+    pub fn send_with(&self, priority: Priority, action: impl Into<<State as Reducer>::Action>) {
+        self.sender.send_with(priority, Ok(action.into()))
+    }
+
+    /// The number of actions that have been dropped, or coalesced, because the `Store`
+    /// was full.
+    ///
+    /// Always zero unless the `Store` was [`bounded`][`StoreBuilder::bounded`].
+    pub fn dropped(&self) -> usize {
+        self.sender.dropped()
+    }
+
+    /// Adds a [`Middleware`] to be called around every action that the `Store` reduces
+    /// from now on.
+    ///
+    /// `Middleware` is called in the order that it was added.
+    pub fn add_middleware<M>(&self, middleware: M)
+    where
+        M: Middleware<State> + Send + 'static,
+        State: 'static,
+    {
+        let middleware: Box<dyn Middleware<State> + Send> = Box::new(middleware);
+        self.sender
+            .send(Err(Control::Middleware(Box::new(middleware))))
+    }
+
+    /// Starts recording every action that the `Store` reduces; until the returned
+    /// [`Recorder`] is dropped.
+    ///
+    /// See the [`recording`] module for more.
+    pub fn record(&self) -> Recorder<<State as Reducer>::Action>
+    where
+        <State as Reducer>::Action: Clone + Send + 'static,
+    {
+        let (recorder, record) = Recorder::new(self.clock.clone());
+        self.sender.send(Err(Control::Record(Box::new(record))));
+
+        recorder
+    }
+
+    /// Sends the actions in `recording` that began each batch; in order.
+    ///
+    /// The `Store` should be new, and created with the same initial state as the one that
+    /// was recorded. As the actions sent by its [`Task`]s are part of the `recording`, the
+    /// `Store` will no longer start any tasks of its own.
+    ///
+    /// # Note
+    /// This is permanent: any tasks that the `Store` is already running are cancelled, as
+    /// are those that any later actions (even those sent after the replay) would start.
+    ///
+    /// [`Task`]: crate::Task
+    pub fn replay(&self, recording: &Recording<<State as Reducer>::Action>)
+    where
+        <State as Reducer>::Action: Clone,
+    {
+        self.sender.send(Err(Control::Cancel));
+
+        for entry in recording.sent() {
+            self.send(entry.action.clone());
+        }
+    }
+
+    /// Calls `f` with the `Store`’s state once it has been registered and then again
+    /// after each batch of actions has been reduced.
+    ///
+    /// A batch is an action along with all of the follow-up [`action`]s that
+    /// it sent while being reduced.
+    ///
+    /// # Note
+    /// `f` is called on the `Store`’s runtime thread and will delay further actions
+    /// while it runs. Prefer [`subscribe`][`Store::subscribe`] for any work that takes
+    /// more than a moment.
+    ///
+    /// [`action`]: crate::effects::Effects::action
+    pub fn watch<F>(&self, mut f: F)
+    where
+        F: FnMut(&State) + Send + 'static,
+        State: 'static,
+    {
+        self.watch_while(move |state| {
+            f(state);
+            true
+        })
+    }
+
+    /// Returns a [`Stream`] of snapshots of the `Store`’s state, as projected by `f`.
+    ///
+    /// The first snapshot is of the state when the subscription was registered;
+    /// then one more is sent after each batch of actions has been reduced.
+    /// Dropping the `Stream` ends the subscription.
+    ///
+    /// ```rust
+    /// # use composable::*;
+    /// # use futures::{executor::block_on, StreamExt};
+    /// #
+    /// #[derive(Default)]
+    /// struct State {
+    ///     n: usize,
+    /// }
+    ///
+    /// #[derive(Debug)]
+    /// enum Action {
+    ///     Increment,
+    /// }
+    ///
+    /// impl Reducer for State {
+    ///     type Action = Action;
+    ///     type Output = Self;
+    ///
+    ///     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+    ///         match action {
+    ///             Action::Increment => self.n += 1,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let store = Store::<State>::default();
+    /// let counts = store.subscribe(|state| state.n);
+    ///
+    /// store.send(Action::Increment);
+    /// store.send(Action::Increment);
+    ///
+    /// let counts = block_on(counts.take(3).collect::<Vec<_>>());
+    /// assert_eq!(counts, [0, 1, 2]);
+    /// # store.into_inner();
+    /// ```
+    pub fn subscribe<T, F>(&self, f: F) -> impl Stream<Item = T> + Send + Unpin
+    where
+        F: Fn(&State) -> T + Send + 'static,
+        T: Send + 'static,
+        State: 'static,
+    {
+        let (sender, receiver) = unbounded();
+        self.watch_while(move |state| sender.unbounded_send(f(state)).is_ok());
+
+        receiver
+    }
+
+    /// Calls `f` with the `Store`’s current state and returns its result.
+    ///
+    /// `f` is run on the `Store`’s runtime thread, between action batches, so it sees
+    /// the effects of every action sent before it — including their follow-up actions.
+    ///
+    /// ```rust
+    /// # use composable::*;
+    /// #
+    /// # #[derive(Default)]
+    /// # struct State {
+    /// #     n: usize,
+    /// # }
+    /// #
+    /// # #[derive(Debug)]
+    /// # enum Action {
+    /// #     Increment,
+    /// # }
+    /// #
+    /// # impl Reducer for State {
+    /// #     type Action = Action;
+    /// #     type Output = Self;
+    /// #
+    /// #     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+    /// #         match action {
+    /// #             Action::Increment => self.n += 1,
+    /// #         }
+    /// #     }
+    /// # }
+    /// #
+    /// let store = Store::<State>::default();
+    ///
+    /// store.send(Action::Increment);
+    /// assert_eq!(store.with_state(|state| state.n), 1);
+    /// # store.into_inner();
+    /// ```
     ///
     /// # Note
-    /// Care should be exercised when using this method in applications that utilize
-    /// asynchronous [`Effects`][`crate::effects::Effects`]. `into_inner` makes a “best effort”
-    /// to wait until any pending tasks are completed but it is not guaranteed.
+    /// This blocks the calling thread until the runtime gets to it. Calling it from
+    /// a [`watch`][`Store::watch`] callback will deadlock; [`with_state_async`] may
+    /// be used from asynchronous code instead.
+    ///
+    /// [`with_state_async`]: Store::with_state_async
+    pub fn with_state<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&State) -> R + Send + 'static,
+        R: Send + 'static,
+        State: 'static,
+    {
+        block_on(self.with_state_async(f))
+    }
+
+    /// The asynchronous version of [`with_state`][`Store::with_state`].
+    pub fn with_state_async<F, R>(&self, f: F) -> impl Future<Output = R> + Send
+    where
+        F: FnOnce(&State) -> R + Send + 'static,
+        R: Send + 'static,
+        State: 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let mut once = Some((f, sender));
+
+        self.watch_while(move |state| {
+            if let Some((f, sender)) = once.take() {
+                sender.send(f(state)).ok();
+            }
+
+            false // a single call is all that is needed
+        });
+
+        receiver.map(|result| result.expect("the Store’s runtime has stopped"))
+    }
+
+    fn watch_while<F>(&self, mut f: F)
+    where
+        F: FnMut(&State) -> bool + Send + 'static,
+        State: 'static,
+    {
+        let watch = move |state: &dyn Any| match state.downcast_ref::<State>() {
+            Some(state) => f(state),
+            None => false,
+        };
+
+        self.sender.send(Err(Control::Watch(Box::new(watch))))
+    }
+
+    /// Stops the `Store`’s runtime and returns its current `state` value.
+    ///
+    /// Waits for the `Store`’s [`Task`]s to finish first; including any started by the
+    /// actions that they send. Use [`into_inner_timeout`][`Store::into_inner_timeout`] if
+    /// some of them may never finish.
+    ///
+    /// Tasks that were [`detach`]ed are not waited for; they are cancelled once the others
+    /// have finished.
+    ///
+    /// [`Task`]: crate::Task
+    /// [`detach`]: crate::Task::detach
     pub fn into_inner(self) -> <State as Reducer>::Output {
-        self.sender.send(Err(std::thread::current()));
-        std::thread::park(); // waiting for any async tasks to finish up
+        self.sender.send(Err(Control::Shutdown));
+        drop(self.sender); // ends the runtime’s (outer) while-let once its tasks are done
+        self.handle
+            .join()
+            .unwrap_or_else(|panic| resume_unwind(panic))
+    }
+
+    /// Stops the `Store`’s runtime and returns its current `state` value; waiting no more
+    /// than `timeout` for the `Store`’s [`Task`]s to finish.
+    ///
+    /// # Errors
+    /// If the tasks do not finish in time they are cancelled, and the `Store`’s final
+    /// `state` is returned within a [`ShutdownError`].
+    ///
+    /// [`Task`]: crate::Task
+    pub fn into_inner_timeout(
+        self,
+        timeout: Duration,
+    ) -> Result<<State as Reducer>::Output, ShutdownError<<State as Reducer>::Output>> {
+        let (idle, receiver) = mpsc::channel();
+        self.sender.send(Err(Control::Idle(idle)));
+
+        match receiver.recv_timeout(timeout) {
+            Ok(()) => Ok(self.into_inner()),
+            Err(_) => Err(ShutdownError(self.shutdown_now())),
+        }
+    }
+
+    /// Cancels all of the `Store`’s running [`Task`]s, stops its runtime and returns
+    /// its current `state` value.
+    ///
+    /// Any actions that were already sent are still reduced, but no new tasks will be
+    /// started for them.
+    ///
+    /// [`Task`]: crate::Task
+    pub fn shutdown_now(self) -> <State as Reducer>::Output {
+        self.sender.send(Err(Control::Cancel));
+        self.into_inner()
+    }
+}
+
+/// The error returned by [`Store::into_inner_timeout`] when the `Store`’s tasks
+/// did not finish in time.
+///
+/// The unfinished tasks have been cancelled, but the `Store`’s final `state`
+/// is still available through [`into_inner`][`ShutdownError::into_inner`].
+pub struct ShutdownError<Output>(Output);
+
+impl<Output> ShutdownError<Output> {
+    /// Returns the `Store`’s final `state` value.
+    pub fn into_inner(self) -> Output {
+        self.0
+    }
+}
+
+impl<Output> Debug for ShutdownError<Output> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ShutdownError").finish_non_exhaustive()
+    }
+}
+
+impl<Output> Display for ShutdownError<Output> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the Store’s tasks did not finish before the timeout")
+    }
+}
+
+impl<Output> Error for ShutdownError<Output> {}
+
+/// Returned by [`Store::send_and_wait`]; a `Future` that resolves once the action has
+/// been reduced, along with every action that it synchronously sent.
+///
+/// Use [`tasks`][`Ticket::tasks`] to also wait for any [`Task`]s it started.
+///
+/// [`Task`]: crate::Task
+#[must_use = "a Ticket does nothing unless awaited; use `Store::send` otherwise"]
+pub struct Ticket {
+    reduced: oneshot::Receiver<()>,
+    finished: oneshot::Receiver<()>,
+}
+
+/// The runtime’s half of a [`Ticket`].
+pub(crate) struct Ticketed {
+    pub(crate) reduced: oneshot::Sender<()>,
+    pub(crate) finished: oneshot::Sender<()>,
+}
+
+impl Ticket {
+    /// Resolves once the action has been reduced, and all of the [`Task`]s started
+    /// while reducing it (and the actions it synchronously sent) have finished; with
+    /// the actions that they sent reduced too.
+    ///
+    /// Tasks started by the actions that _those_ tasks send are not waited for.
+    ///
+    /// [`Task`]: crate::Task
+    pub fn tasks(self) -> impl Future<Output = ()> + Send {
+        self.finished
+            .map(|result| result.expect("the Store’s runtime has stopped"))
+    }
+}
+
+impl Future for Ticket {
+    type Output = ();
 
-        drop(self.sender); // ends the runtime’s (outer) while-let
-        std::thread::yield_now(); // give it time to shut down
-        self.handle.join().unwrap()
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.reduced
+            .poll_unpin(cx)
+            .map(|result| result.expect("the Store’s runtime has stopped"))
     }
 }
 
 impl<State: Reducer> Default for Store<State>
 where
-    State: Default,
-    <State as Reducer>::Action: Send + 'static,
+    State: Default + 'static,
+    <State as Reducer>::Action: Traced + Send + 'static,
     <State as Reducer>::Output: Send + From<State> + 'static,
 {
     /// Creates a new `Store` with a default initial state.
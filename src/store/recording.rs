@@ -0,0 +1,147 @@
+//! Recording the actions reduced by a [`Store`] so that they can be replayed later.
+//!
+//! A [`Recording`] of the actions that led to a bug report can be replayed into a
+//! [`TestStore`] to reproduce it deterministically.
+//!
+//! With the `serde` feature enabled, a `Recording` can be serialized along with its `Action`s.
+//!
+//! [`Store`]: crate::Store
+//! [`TestStore`]: crate::TestStore
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::Clock;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How an action reached the `Store`’s [`Reducer`][`crate::Reducer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Source {
+    /// Sent to the `Store`; either with [`Store::send`] or by one of its [`Task`]s.
+    /// Each of these begins a new batch of actions.
+    ///
+    /// [`Store::send`]: crate::Store::send
+    /// [`Task`]: crate::Task
+    Sent,
+    /// Sent with [`Effects::action`] while reducing another action in the same batch.
+    ///
+    /// [`Effects::action`]: crate::effects::Effects::action
+    Effect,
+}
+
+/// A single recorded action.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Entry<Action> {
+    /// How the action reached the `Reducer`.
+    pub source: Source,
+    /// When the action was reduced; relative to the start of the recording, and by the
+    /// `Store`’s [`Clock`] if it has one.
+    pub at: Duration,
+    /// The action itself.
+    pub action: Action,
+}
+
+/// The actions reduced by a `Store`; in the order that they were reduced.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Recording<Action> {
+    entries: Vec<Entry<Action>>,
+}
+
+impl<Action> Default for Recording<Action> {
+    fn default() -> Self {
+        Recording {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<Action> Recording<Action> {
+    /// The recorded actions.
+    pub fn entries(&self) -> &[Entry<Action>] {
+        &self.entries
+    }
+
+    /// The actions that began each batch; those that need to be sent to replay the recording.
+    pub fn sent(&self) -> impl Iterator<Item = &Entry<Action>> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.source == Source::Sent)
+    }
+}
+
+impl<Action> From<Vec<Entry<Action>>> for Recording<Action> {
+    fn from(entries: Vec<Entry<Action>>) -> Self {
+        Recording { entries }
+    }
+}
+
+impl<Action> From<Recording<Action>> for Vec<Entry<Action>> {
+    fn from(recording: Recording<Action>) -> Self {
+        recording.entries
+    }
+}
+
+/// Records the actions reduced by a `Store`, for as long as it is kept.
+///
+/// This `struct` is created by the [`record`][`crate::Store::record`] method on `Store`.
+pub struct Recorder<Action> {
+    shared: Arc<Mutex<Recording<Action>>>,
+}
+
+impl<Action> Recorder<Action> {
+    /// Returns a copy of everything recorded so far.
+    pub fn recording(&self) -> Recording<Action>
+    where
+        Action: Clone,
+    {
+        self.lock().clone()
+    }
+
+    /// Returns everything recorded so far; leaving the `Recorder` to continue from empty.
+    pub fn take(&self) -> Recording<Action> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Recording<Action>> {
+        self.shared.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// The runtime’s side of a [`Recorder`].
+///
+/// Called with each action before it is reduced. It returns `false` once its
+/// `Recorder` has been dropped.
+pub(crate) type Record<Action> = Box<dyn FnMut(Source, &Action) -> bool + Send>;
+
+impl<Action: Clone + Send + 'static> Recorder<Action> {
+    /// A `Recorder` that times its actions by `clock`; or by the wall clock, if `None`.
+    pub(crate) fn new(clock: Option<Clock>) -> (Self, Record<Action>) {
+        let shared = Arc::new(Mutex::new(Recording::default()));
+        let weak = Arc::downgrade(&shared);
+
+        let now = move || clock.as_ref().map_or_else(Instant::now, Clock::now);
+        let start = now();
+
+        let record = move |source, action: &Action| match weak.upgrade() {
+            None => false,
+            Some(shared) => {
+                let mut recording = shared.lock().unwrap_or_else(|err| err.into_inner());
+                recording.entries.push(Entry {
+                    source,
+                    at: now().saturating_duration_since(start),
+                    action: action.clone(),
+                });
+
+                true
+            }
+        };
+
+        (Recorder { shared }, Box::new(record))
+    }
+}
@@ -1,92 +1,258 @@
 use std::cell::RefCell;
 use std::collections::VecDeque;
+use std::iter::repeat_n;
+use std::mem::discriminant;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::rc::Rc;
-use std::thread::{Builder, Thread};
+use std::sync::{Arc, Mutex};
+use std::thread::Builder;
+use std::time::Instant;
 
+use futures::channel::oneshot;
 use futures::executor::LocalPool;
-use futures::task::LocalSpawnExt;
 use futures::{pin_mut, StreamExt};
 
-use crate::dependencies::with_dependency;
-use crate::effects::Executor;
+use crate::dependencies::{with_dependencies, with_dependency, Dependency};
+use crate::effects::scheduler::Reactor;
+use crate::effects::{Executor, Tasks};
 use crate::reducer::Reducer;
-use crate::store::channel::{channel, WeakSender};
-use crate::store::Store;
+use crate::store::cause::{Cause, Tracer};
+use crate::store::channel::{bounded, Receiver, WeakSender};
+use crate::store::metrics::{Collector, Metrics};
+use crate::store::middleware::{reduce, Added, Middleware};
+use crate::store::recording::{Record, Source};
+use crate::store::supervision::{Health, Supervisor};
+use crate::store::{Control, Store, StoreBuilder, Ticketed, Watcher};
 
 impl<State: Reducer> Store<State> {
-    pub(crate) fn runtime<F>(with: F) -> Self
+    pub(crate) fn runtime<F>(with: F, settings: StoreBuilder<State>) -> Self
     where
         F: (FnOnce() -> State) + Send + 'static,
+        State: 'static,
         <State as Reducer>::Action: Send + 'static,
         <State as Reducer>::Output: Send + From<State> + 'static,
     {
-        let (sender, receiver) = channel();
-        let actions: WeakSender<Result<<State as Reducer>::Action, Thread>> = sender.downgrade();
+        let (sender, receiver) = bounded(settings.bounds);
+        let receiver = receiver.upgrade().unwrap(); // before `into_inner` can drop the `sender`
+        let actions: WeakSender<Result<<State as Reducer>::Action, Control>> = sender.downgrade();
+
+        let health = Arc::<Mutex<Health>>::default();
+        let (policy, on_panic) = (settings.supervision, settings.on_panic);
+        let (tracer, clock) = (settings.tracer, settings.clock);
+        let recorded = clock.clone(); // for the timing of its `Recording`s
+        let reported = health.clone();
 
         let handle = Builder::new()
             .name(std::any::type_name::<State>().into())
             .spawn(move || {
                 let mut unthreaded = LocalPool::new();
-                let spawner = unthreaded.spawner();
-
-                let mut state = with();
-                let receiver = receiver.upgrade().unwrap();
-                let effects = Rc::new(RefCell::new(VecDeque::new()));
-
-                let executor = Executor::new(spawner.clone(), actions);
-
-                with_dependency(executor, || {
-                    unthreaded.run_until(async {
-                        pin_mut!(receiver);
-                        while let Some(result) = receiver.next().await {
-                            match result {
-                                Ok(action) => {
-                                    state.reduce(action, Rc::downgrade(&effects));
-
-                                    // wrapping the `borrow_mut` in a closure to ensure that the
-                                    // `borrow_mut` is dropped immediately so that the action is
-                                    // free to push further actions to `effects`
-                                    let next = || effects.borrow_mut().pop_front();
-
-                                    while let Some(action) = next() {
-                                        state.reduce(action, Rc::downgrade(&effects));
-                                    }
-                                }
-                                Err(parked) => {
-                                    spawner
-                                        // `unpark` a thread that is waiting for the store to shut down;
-                                        //  we use a future so that it happens after other (waiting) futures
-                                        //
-                                        //  See: `Store::into_inner` for the other side of this
-                                        .spawn_local(async move {
-                                            parked.unpark();
-                                        })
-                                        .expect("unpark");
-                                }
-                            }
-                        }
-                    });
-
-                    state.into()
-                })
+                let executor = Executor::new(unthreaded.spawner(), actions);
+                let tasks = executor.tasks.clone();
+                let supervisor = Supervisor::new(policy, on_panic, reported);
+
+                let runtime = || {
+                    with_dependency(executor, || {
+                        let added = Added::default(); // only through the channel
+                        let running = run(with(), receiver, tasks, added, supervisor, tracer);
+                        let state = unthreaded.run_until(running);
+                        state.into()
+                    })
+                };
+
+                match clock {
+                    None => runtime(),
+                    Some(clock) => with_dependencies((Reactor::with_clock(&clock), clock), runtime),
+                }
             })
             .unwrap();
 
-        Store { sender, handle }
+        Store {
+            sender,
+            handle,
+            health,
+            clock: recorded,
+        }
+    }
+}
+
+/// Reduces each batch of actions received, and handles any [`Control`] messages,
+/// until all of the `receiver`’s senders have been dropped.
+///
+/// The [`Executor`] for `tasks` must be the current dependency whenever this is polled.
+///
+/// Any [`Middleware`] `added` directly is applied from the next message received; as well
+/// as those sent through the `receiver`.
+///
+/// Panics in the `State`’s [`Reducer`] are handled by the `supervisor`, and each action
+/// is reduced within a span opened by the `tracer`.
+pub(crate) async fn run<State>(
+    mut state: State,
+    receiver: Receiver<Result<<State as Reducer>::Action, Control>>,
+    tasks: Rc<Tasks>,
+    added: Added<State>,
+    mut supervisor: Supervisor<State>,
+    tracer: Tracer<<State as Reducer>::Action>,
+) -> State
+where
+    State: Reducer + 'static,
+    <State as Reducer>::Action: 'static,
+{
+    let effects = Rc::new(RefCell::new(VecDeque::new()));
+    let mut causes = VecDeque::new(); // the span of the action that sent each of the `effects`
+    supervisor.succeeded(&state);
+
+    let mut watchers: Vec<Watcher> = Vec::new();
+    let mut middleware: Vec<Box<dyn Middleware<State>>> = Vec::new();
+    let mut recorders: Vec<Record<<State as Reducer>::Action>> = Vec::new();
+    let mut metrics = Collector::default();
+
+    pin_mut!(receiver);
+    while let Some((result, cause)) = receiver.next().await {
+        metrics.received(receiver.len());
+        middleware.append(&mut added.borrow_mut());
+
+        let (action, ticket) = match result {
+            Ok(action) => (action, None),
+            Err(Control::Ticket(boxed)) => {
+                match boxed.downcast::<(<State as Reducer>::Action, Ticketed)>() {
+                    Ok(boxed) => (boxed.0, Some(boxed.1)),
+                    Err(_) => continue,
+                }
+            }
+            Err(Control::Watch(mut watch)) => {
+                if watch(&state) {
+                    watchers.push(watch);
+                }
+
+                continue;
+            }
+            Err(Control::Middleware(layer)) => {
+                if let Ok(layer) = layer.downcast::<Box<dyn Middleware<State> + Send>>() {
+                    middleware.push(*layer);
+                }
+
+                continue;
+            }
+            Err(Control::Record(record)) => {
+                if let Ok(record) = record.downcast::<Record<<State as Reducer>::Action>>() {
+                    recorders.push(*record);
+                }
+
+                continue;
+            }
+            Err(Control::Idle(idle)) => {
+                tasks.when_idle(idle);
+                continue;
+            }
+            Err(Control::Cancel) => {
+                tasks.cancel();
+                continue;
+            }
+            Err(Control::Shutdown) => {
+                tasks.shutdown();
+                continue;
+            }
+            Err(Control::Metrics(sender)) => {
+                type Snapshot<Action> = oneshot::Sender<Metrics<Action>>;
+                if let Ok(sender) = sender.downcast::<Snapshot<<State as Reducer>::Action>>() {
+                    // the default `Reactor` is only created once a timer is
+                    let delays = Dependency::<Reactor>::new().map_or(0, |r| r.pending().len());
+                    let snapshot = metrics.snapshot(receiver.len(), tasks.len(), delays);
+                    sender.send(snapshot).ok();
+                }
+
+                continue;
+            }
+        };
+
+        let first = tasks.next();
+
+        let batch = catch_unwind(AssertUnwindSafe(|| {
+            let mut reduce = |action, cause: Cause, causes: &mut VecDeque<Cause>| {
+                let (kind, start) = (discriminant(&action), Instant::now());
+                let span = tracer.reducing(&action, &cause);
+                let send = Rc::downgrade(&effects);
+                span.in_scope(|| reduce(&mut middleware, &mut state, action, send));
+                metrics.reduced(kind, start.elapsed());
+
+                let sent = effects.borrow().len() - causes.len();
+                causes.extend(repeat_n(span, sent));
+            };
+
+            recorders.retain_mut(|record| record(Source::Sent, &action));
+            reduce(action, cause, &mut causes);
+
+            // wrapping the `borrow_mut` in a closure to ensure that the
+            // `borrow_mut` is dropped immediately so that the action is
+            // free to push further actions to `effects`
+            let next = || effects.borrow_mut().pop_front();
+
+            while let Some(action) = next() {
+                let cause = causes.pop_front().unwrap_or_else(Cause::current);
+                recorders.retain_mut(|record| record(Source::Effect, &action));
+                reduce(action, cause, &mut causes);
+            }
+        }));
+
+        match batch {
+            Ok(()) => supervisor.succeeded(&state),
+            Err(panic) => {
+                effects.borrow_mut().clear(); // the rest of the batch is abandoned
+                causes.clear();
+                state = supervisor.panicked(panic);
+            }
+        }
+
+        watchers.retain_mut(|watch| watch(&state));
+        tasks.settle(); // the batch may have detached some of the tasks being waited for
+
+        if let Some(Ticketed { reduced, finished }) = ticket {
+            reduced.send(()).unwrap_or_default();
+
+            let actions = Dependency::<Executor<Result<_, Control>>>::new()
+                .map(|executor| executor.actions.clone())
+                .expect("the runtime’s Executor");
+
+            tasks.when_finished(first..tasks.next(), move || {
+                // queued behind any actions that the tasks sent; so they are reduced first
+                let mut finished = Some(finished);
+                let notify: Watcher = Box::new(move |_| {
+                    if let Some(finished) = finished.take() {
+                        finished.send(()).unwrap_or_default();
+                    }
+
+                    false
+                });
+
+                if let Some(actions) = actions.upgrade() {
+                    actions.send(Err::<<State as Reducer>::Action, _>(Control::Watch(notify)));
+                }
+            });
+        }
     }
+
+    state
 }
 
 #[cfg(test)]
 pub mod tests {
-    use std::sync::{Arc, Mutex};
+    use std::iter::repeat_with;
+    use std::sync::{mpsc, Arc, Mutex};
+    use std::time::Duration;
 
+    use futures::executor::block_on;
+    use futures::stream::{iter, pending};
     #[cfg(not(miri))]
     use ntest_timeout::timeout;
 
-    use crate::Effects;
+    use crate::effects::{Delay, Effects as _};
+    use crate::{Clock, CorrelationId, Effects, Health, Interval, Overflow, Priority};
+    use crate::{Supervision, TestStore};
 
     use super::*;
 
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
     #[derive(Clone, Debug, Default)]
     pub struct State {
         pub characters: Arc<Mutex<Vec<char>>>,
@@ -110,11 +276,32 @@ pub mod tests {
                 External(ch) => {
                     self.characters.lock().unwrap().push(ch);
 
-                    if ch == '1' {
-                        send.action(Internal('A'));
-                        send.action(Internal('B'));
-                        send.action(Internal('C'));
-                        send.action(Internal('D'));
+                    match ch {
+                        '1' => {
+                            send.action(Internal('A'));
+                            send.action(Internal('B'));
+                            send.action(Internal('C'));
+                            send.action(Internal('D'));
+                        }
+                        'F' => send.future(async { Some(Internal('f')) }),
+                        'P' => send.stream(pending()),
+                        'L' => {
+                            let low = send.with_priority(Priority::Low);
+                            low.stream(iter([Internal('l'), Internal('m')]));
+                            send.stream(iter([Internal('n'), Internal('o')]));
+                        }
+                        'T' => {
+                            let hour = send.now() + HOUR; // a `future`, so it is waited for
+                            send.future(async move {
+                                Delay::new(hour).await;
+                                Some(Internal('t'))
+                            })
+                        }
+                        'E' => send
+                            .every(Interval::Leading(Duration::from_millis(1)), Internal('e'))
+                            .detach(),
+                        '!' => panic!("a bad action"),
+                        _ => {}
                     }
                 }
             }
@@ -166,6 +353,561 @@ pub mod tests {
         assert_eq!(*values, vec!['1', 'A', 'B', 'C', 'D', '2', '3']);
     }
 
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    /// `with_state` is queued behind the actions sent before it; including their side-effects.
+    fn test_with_state_ordering() {
+        let store = Store::<State>::default();
+
+        use Action::*;
+        store.send(External('1'));
+        let values = store.with_state(|state| state.characters.lock().unwrap().clone());
+        assert_eq!(values, vec!['1', 'A', 'B', 'C', 'D']);
+
+        store.send(External('2'));
+        let length =
+            block_on(store.with_state_async(|state| state.characters.lock().unwrap().len()));
+        assert_eq!(length, 6);
+
+        store.into_inner();
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    /// Subscribers see the state once per action batch; never part way through one.
+    fn test_subscribers_see_whole_batches() {
+        let store = Store::<State>::default();
+        let lengths = store.subscribe(|state| state.characters.lock().unwrap().len());
+
+        use Action::*;
+        store.send(External('1'));
+        store.send(External('2'));
+
+        let lengths = block_on(lengths.take(3).collect::<Vec<_>>());
+        assert_eq!(lengths, [0, 5, 6]);
+
+        store.into_inner();
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    /// Every action sent by several threads at once is reduced exactly once; and those of
+    /// each thread in the order that it sent them.
+    fn test_concurrent_producers() {
+        const PRODUCERS: u32 = 8;
+        const SENDS: u32 = 1000;
+
+        let store = Store::<State>::default();
+        let sent = |producer, n| char::from_u32(0x4E00 + producer * SENDS + n).unwrap();
+
+        std::thread::scope(|scope| {
+            for producer in 0..PRODUCERS {
+                let store = &store;
+                scope.spawn(move || {
+                    for n in 0..SENDS {
+                        store.send(Action::Internal(sent(producer, n)));
+                    }
+                });
+            }
+        });
+
+        let state = store.into_inner();
+        let values = state.characters.lock().unwrap();
+        assert_eq!(values.len(), (PRODUCERS * SENDS) as usize);
+
+        for producer in 0..PRODUCERS {
+            let range = sent(producer, 0)..=sent(producer, SENDS - 1);
+            let received = values.iter().filter(|ch| range.contains(ch));
+            assert!(received.copied().eq((0..SENDS).map(|n| sent(producer, n))));
+        }
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_into_inner_waits_for_tasks() {
+        let store = Store::<State>::default();
+
+        use Action::*;
+        store.send(External('1'));
+        store.send(External('F'));
+
+        let state = store.into_inner();
+        let values = state.characters.lock().unwrap();
+        assert_eq!(*values, vec!['1', 'A', 'B', 'C', 'D', 'F', 'f']);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_into_inner_cancels_detached_tasks() {
+        let store = Store::builder()
+            .clock(Clock::scaled(1.0))
+            .with_initial(State::default());
+
+        use Action::*;
+        store.send(External('E')); // a detached `every` that never finishes
+        store.send(External('F')); // while a `future` is still waited for
+
+        let state = store.into_inner();
+        let values = state.characters.lock().unwrap();
+        assert_eq!(values[0], 'E');
+        assert!(values.contains(&'F') && values.contains(&'f')); // among any `e`s
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_into_inner_timeout_cancels_tasks() {
+        let store = Store::<State>::default();
+
+        use Action::*;
+        store.send(External('P')); // a task that never finishes
+        store.send(External('2'));
+
+        let state = store
+            .into_inner_timeout(Duration::from_millis(10))
+            .expect_err("the pending task cannot finish")
+            .into_inner();
+
+        let values = state.characters.lock().unwrap();
+        assert_eq!(*values, vec!['P', '2']);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_into_inner_timeout_without_tasks() {
+        let store = Store::<State>::default();
+
+        use Action::*;
+        store.send(External('F'));
+
+        let state = store.into_inner_timeout(Duration::from_secs(5)).unwrap();
+        let values = state.characters.lock().unwrap();
+        assert_eq!(*values, vec!['F', 'f']);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_shutdown_now() {
+        let store = Store::<State>::default();
+
+        use Action::*;
+        store.send(External('P'));
+        store.send(External('F')); // its future is never started
+
+        let state = store.shutdown_now();
+        let values = state.characters.lock().unwrap();
+        assert_eq!(*values, vec!['P', 'F']);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_middleware_sees_every_action() {
+        struct Log(Arc<Mutex<Vec<String>>>);
+
+        impl Middleware<State> for Log {
+            fn before(&mut self, action: &Action, _state: &State) -> bool {
+                self.0.lock().unwrap().push(format!("{action:?}"));
+                *action != External('X')
+            }
+
+            fn after(&mut self, state: &State) {
+                let len = state.characters.lock().unwrap().len();
+                self.0.lock().unwrap().push(len.to_string());
+            }
+        }
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let store = Store::<State>::default();
+        store.add_middleware(Log(log.clone()));
+
+        use Action::*;
+        store.send(External('X')); // filtered out
+        store.send(External('1'));
+
+        let state = store.into_inner();
+        assert_eq!(
+            *state.characters.lock().unwrap(),
+            vec!['1', 'A', 'B', 'C', 'D']
+        );
+
+        #[rustfmt::skip]
+        assert_eq!(*log.lock().unwrap(), vec![
+            "External('X')",
+            "External('1')", "1",
+            "Internal('A')", "2",
+            "Internal('B')", "3",
+            "Internal('C')", "4",
+            "Internal('D')", "5",
+        ]);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_recordings_replay() {
+        let store = Store::<State>::default();
+        let recorder = store.record();
+
+        use Action::*;
+        store.send(External('1'));
+        store.send(External('F')); // `f` is sent by a task
+        store.send(External('2'));
+
+        let original = store.into_inner();
+        let recording = recorder.take();
+        assert_eq!(recording.entries().len(), 8);
+        assert_eq!(recording.sent().count(), 4);
+
+        let store = Store::<State>::default();
+        store.replay(&recording);
+        assert_eq!(store.into_inner(), original);
+
+        let mut store = TestStore::<State>::default();
+        store.replay(&recording, |state| *state = original.clone());
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_recordings_use_the_clock() {
+        let clock = Clock::manual();
+        let store = Store::builder()
+            .clock(clock.clone())
+            .with_initial(State::default());
+        let recorder = store.record();
+
+        use Action::*;
+        block_on(store.send_and_wait(External('a')));
+        clock.advance(HOUR);
+        store.send(External('b'));
+        store.into_inner();
+
+        let at = recorder
+            .take()
+            .entries()
+            .iter()
+            .map(|entry| entry.at)
+            .collect::<Vec<_>>();
+        assert_eq!(at, [Duration::ZERO, HOUR]);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_send_and_wait() {
+        let store = Store::<State>::default();
+        let characters = store.with_state(|state| state.characters.clone());
+
+        use Action::*;
+        block_on(store.send_and_wait(External('1')));
+        assert_eq!(*characters.lock().unwrap(), ['1', 'A', 'B', 'C', 'D']);
+
+        block_on(store.send_and_wait(External('F')).tasks());
+        assert_eq!(characters.lock().unwrap()[5..], ['F', 'f']);
+
+        block_on(store.send_and_wait(External('P'))); // would never finish its `tasks`
+        store.shutdown_now();
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_priorities() {
+        let (store, resume) = paused(8, Overflow::DropOldest);
+
+        use Action::*;
+        store.send_with(Priority::Low, External('a'));
+        store.send(External('b'));
+        store.send_with(Priority::High, External('c'));
+        store.send(External('1')); // its follow-up actions still run straight after it
+        store.send_with(Priority::High, External('d'));
+
+        drop(resume);
+        let state = store.into_inner();
+        let characters = state.characters.lock().unwrap().iter().collect::<String>();
+        assert_eq!(characters, "cdb1ABCDa");
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    /// The actions of a `Priority::Low` task are reduced after those queued at `Normal`;
+    /// even though it was started, and sent them, first.
+    fn test_with_priority() {
+        let store = Store::<State>::default();
+
+        use Action::*;
+        store.send(External('L'));
+
+        let state = store.into_inner();
+        let characters = state.characters.lock().unwrap().iter().collect::<String>();
+        assert_eq!(characters, "Lnolm");
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_correlation_ids() {
+        struct Origins(Arc<Mutex<Vec<(Action, CorrelationId)>>>);
+
+        impl Middleware<State> for Origins {
+            fn before(&mut self, action: &Action, _state: &State) -> bool {
+                let id = Dependency::<CorrelationId>::new().copied().unwrap();
+                self.0.lock().unwrap().push((action.clone(), id));
+                true
+            }
+        }
+
+        let origins = Arc::new(Mutex::new(Vec::new()));
+        let store = Store::<State>::default();
+        store.add_middleware(Origins(origins.clone()));
+
+        use Action::*;
+        store.send(External('1')); // sends `A` to `D` through `Effects::action`
+        store.send(External('F')); // sends `f` from a future
+        store.send(External('2'));
+        store.into_inner();
+
+        let origins = origins.lock().unwrap();
+        let origin = |action: Action| origins.iter().find(|(sent, _)| *sent == action).unwrap().1;
+
+        for effect in "ABCD".chars() {
+            assert_eq!(origin(Internal(effect)), origin(External('1')));
+        }
+
+        assert_eq!(origin(Internal('f')), origin(External('F')));
+        assert_ne!(origin(External('F')), origin(External('1')));
+        assert_ne!(origin(External('2')), origin(External('F')));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_metrics() {
+        let (store, resume) = paused(8, Overflow::Block);
+
+        use Action::*;
+        store.send(External('a'));
+        store.send(External('1'));
+        store.send(External('P')); // a task that never finishes
+
+        drop(resume);
+        store.with_state(|_| ()); // every action has been reduced
+
+        let metrics = store.metrics();
+        assert_eq!(metrics.timing(&External('?')).count, 3);
+        assert_eq!(metrics.timing(&Internal('?')).count, 4);
+        assert_eq!(metrics.total().count, 7);
+        assert!(metrics.high_water >= 3);
+        assert_eq!(metrics.queued, 0);
+        assert_eq!(metrics.tasks, 1);
+        assert_eq!(metrics.delays, 0);
+
+        store.shutdown_now();
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_metrics_delays() {
+        let clock = Clock::manual();
+        let store = Store::builder()
+            .clock(clock.clone())
+            .with_initial(State::default());
+
+        use Action::*;
+        store.send(External('T')); // a task waiting an hour for its timer
+
+        // the timer is only added once its task is first polled
+        let metrics = repeat_with(|| store.metrics()).find(|metrics| metrics.delays == 1);
+        assert_eq!(metrics.unwrap().tasks, 1);
+
+        clock.advance(HOUR);
+        let metrics = repeat_with(|| store.metrics()).find(|metrics| metrics.tasks == 0);
+        assert_eq!(metrics.unwrap().delays, 0);
+
+        let state = store.into_inner();
+        assert_eq!(*state.characters.lock().unwrap(), ['T', 't']);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_manual_clock() {
+        let clock = Clock::manual();
+        let store = Store::builder()
+            .clock(clock.clone())
+            .with_initial(State::default());
+        let characters = store.with_state(|state| state.characters.clone());
+
+        use Action::*;
+        block_on(store.send_and_wait(External('T')));
+        clock.advance(HOUR - Duration::from_secs(1));
+        store.with_state(|_| ());
+        assert_eq!(*characters.lock().unwrap(), ['T']);
+
+        clock.advance(Duration::from_secs(1));
+        store.into_inner(); // waits for the timer’s task
+        assert_eq!(*characters.lock().unwrap(), ['T', 't']);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_scaled_clock() {
+        let store = Store::builder()
+            .clock(Clock::scaled(60.0 * 60.0 * 10.0)) // an hour every tenth of a second
+            .with_initial(State::default());
+        let characters = store.with_state(|state| state.characters.clone());
+
+        use Action::*;
+        store.send(External('T'));
+        store.into_inner();
+        assert_eq!(*characters.lock().unwrap(), ['T', 't']);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_supervised_restart() {
+        let (panicked, panics) = mpsc::channel();
+        let store = Store::builder()
+            .supervise(Supervision::Restart(Box::new(State::default)))
+            .on_panic(move |message| panicked.send(message.to_string()).unwrap())
+            .with_initial(State::default());
+
+        use Action::*;
+        store.send(External('a'));
+        store.send(External('!'));
+        store.send(External('b'));
+
+        store.with_state(|_| ()); // after all three have been reduced
+        assert_eq!(panics.recv().unwrap(), "a bad action");
+
+        let expected = Health::Recovered {
+            panics: 1,
+            message: String::from("a bad action"),
+        };
+        assert_eq!(store.health(), expected);
+
+        let state = store.into_inner();
+        assert_eq!(*state.characters.lock().unwrap(), ['b']);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_unsupervised_panic() {
+        let store = Store::<State>::default();
+        store.send(Action::External('!'));
+
+        while store.health() == Health::Healthy {
+            std::thread::yield_now();
+        }
+
+        let stopped = Health::Stopped {
+            message: String::from("a bad action"),
+        };
+        assert_eq!(store.health(), stopped);
+
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| store.into_inner()));
+        assert!(result.is_err());
+    }
+
+    /// A bounded `Store` whose runtime is held, inside a `with_state` call, until the
+    /// returned sender is used (or dropped).
+    fn paused(capacity: usize, overflow: Overflow<Action>) -> (Store<State>, mpsc::Sender<()>) {
+        let store = Store::builder()
+            .bounded(capacity, overflow)
+            .with_initial(State::default());
+
+        let (entered, waiting) = mpsc::channel();
+        let (resume, gate) = mpsc::channel::<()>();
+        drop(store.with_state_async(move |_| {
+            entered.send(()).unwrap();
+            gate.recv().ok();
+        }));
+
+        waiting.recv().unwrap();
+        (store, resume)
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_bounded_drop_newest() {
+        let (store, resume) = paused(2, Overflow::DropNewest);
+
+        use Action::*;
+        "abcd".chars().for_each(|ch| store.send(External(ch)));
+        assert_eq!(store.dropped(), 2);
+
+        drop(resume);
+        let state = store.into_inner();
+        assert_eq!(*state.characters.lock().unwrap(), ['a', 'b']);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_bounded_drop_oldest() {
+        let (store, resume) = paused(2, Overflow::DropOldest);
+
+        use Action::*;
+        "abcd".chars().for_each(|ch| store.send(External(ch)));
+        assert_eq!(store.dropped(), 2);
+
+        drop(resume);
+        let state = store.into_inner();
+        assert_eq!(*state.characters.lock().unwrap(), ['c', 'd']);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_bounded_coalesce() {
+        let (store, resume) = paused(2, Overflow::coalesce_by_key(std::mem::discriminant));
+
+        use Action::*;
+        store.send(External('a'));
+        store.send(Internal('x'));
+        store.send(External('b')); // supersedes `a`
+        store.send(Internal('y')); // supersedes `x`
+        store.send(External('c')); // supersedes `b`
+        assert_eq!(store.dropped(), 3);
+
+        drop(resume);
+        let state = store.into_inner();
+        assert_eq!(*state.characters.lock().unwrap(), ['y', 'c']); // in the order sent
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    #[timeout(10000)]
+    fn test_bounded_block() {
+        let (store, resume) = paused(2, Overflow::Block);
+
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            drop(resume);
+        });
+
+        use Action::*;
+        "abcd".chars().for_each(|ch| store.send(External(ch))); // blocks until resumed
+        assert_eq!(store.dropped(), 0);
+
+        let state = store.into_inner();
+        assert_eq!(*state.characters.lock().unwrap(), ['a', 'b', 'c', 'd']);
+    }
+
     #[test]
     #[cfg(not(miri))]
     #[timeout(10000)]
@@ -0,0 +1,145 @@
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+
+/// What a [`Store`][`crate::Store`] does when its [`Reducer`][`crate::Reducer`] panics.
+///
+/// See: [`StoreBuilder::supervise`][`crate::StoreBuilder::supervise`]
+pub enum Supervision<State> {
+    /// The `Store`’s runtime stops; any further actions sent to it are ignored and
+    /// [`into_inner`][`crate::Store::into_inner`] resumes the panic.
+    ///
+    /// This is the default.
+    Stop,
+    /// The `State` is restored to a copy of itself from before the batch of actions
+    /// that panicked, and the `Store` carries on.
+    ///
+    /// The copy is taken after every batch of actions.
+    ///
+    /// See: [`restore`][`Supervision::restore`]
+    Restore(fn(&State) -> State),
+    /// The `State` is replaced with a new one from the function, and the `Store` carries on.
+    Restart(Box<dyn FnMut() -> State + Send>),
+}
+
+impl<State: Clone> Supervision<State> {
+    /// Restores the last good `State` by cloning it.
+    pub fn restore() -> Self {
+        Supervision::Restore(State::clone)
+    }
+}
+
+/// Whether a [`Store`][`crate::Store`]’s [`Reducer`][`crate::Reducer`] has panicked,
+/// and what became of it.
+///
+/// See: [`Store::health`][`crate::Store::health`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Health {
+    /// The `Reducer` has never panicked.
+    #[default]
+    Healthy,
+    /// The `Reducer` has panicked `panics` times, but the `Store` has recovered each time.
+    Recovered {
+        panics: usize,
+        /// The message of the most recent panic.
+        message: String,
+    },
+    /// The `Reducer` panicked and the `Store` has stopped.
+    Stopped {
+        /// The message of the panic.
+        message: String,
+    },
+}
+
+impl Display for Health {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Health::Healthy => write!(f, "healthy"),
+            Health::Recovered { panics, message } => {
+                write!(
+                    f,
+                    "recovered from {panics} panic(s); most recently: {message}"
+                )
+            }
+            Health::Stopped { message } => write!(f, "stopped by a panic: {message}"),
+        }
+    }
+}
+
+/// Called with the message of every panic; see [`StoreBuilder::on_panic`][`crate::StoreBuilder::on_panic`].
+pub(crate) type OnPanic = Box<dyn FnMut(&str) + Send>;
+
+/// Applies a [`Supervision`] policy within the runtime.
+pub(crate) struct Supervisor<State> {
+    policy: Supervision<State>,
+    health: Arc<Mutex<Health>>,
+    on_panic: Option<OnPanic>,
+    good: Option<State>, // for `Supervision::Restore`
+}
+
+impl<State> Default for Supervisor<State> {
+    fn default() -> Self {
+        Supervisor::new(Supervision::Stop, None, Default::default())
+    }
+}
+
+impl<State> Supervisor<State> {
+    pub(crate) fn new(
+        policy: Supervision<State>,
+        on_panic: Option<OnPanic>,
+        health: Arc<Mutex<Health>>,
+    ) -> Self {
+        Supervisor {
+            policy,
+            health,
+            on_panic,
+            good: None,
+        }
+    }
+
+    /// Called with the initial `state`, and after every batch that did not panic.
+    pub(crate) fn succeeded(&mut self, state: &State) {
+        if let Supervision::Restore(copy) = self.policy {
+            self.good = Some(copy(state));
+        }
+    }
+
+    /// Called when a batch panicked; returns the `State` to carry on with, or
+    /// resumes the panic.
+    pub(crate) fn panicked(&mut self, panic: Box<dyn Any + Send>) -> State {
+        let message = match (panic.downcast_ref::<&str>(), panic.downcast_ref::<String>()) {
+            (Some(message), _) => message.to_string(),
+            (_, Some(message)) => message.clone(),
+            _ => String::from("Box<dyn Any>"),
+        };
+
+        if let Some(on_panic) = self.on_panic.as_mut() {
+            on_panic(&message);
+        }
+
+        let mut health = self.health.lock().unwrap_or_else(|err| err.into_inner());
+        let panics = match &*health {
+            Health::Recovered { panics, .. } => panics + 1,
+            _ => 1,
+        };
+
+        let state = match &mut self.policy {
+            Supervision::Stop => None,
+            Supervision::Restore(copy) => self.good.as_ref().map(copy),
+            Supervision::Restart(with) => Some(with()),
+        };
+
+        match state {
+            Some(state) => {
+                *health = Health::Recovered { panics, message };
+                state
+            }
+            None => {
+                *health = Health::Stopped { message };
+                drop(health);
+
+                std::panic::resume_unwind(panic)
+            }
+        }
+    }
+}
@@ -81,7 +81,34 @@ use std::time::Duration;
 /// store.advance(Duration::from_secs(1));
 /// store.advance(Duration::from_secs(1));
 /// store.recv(Recv, |state| state.n = 2);
+///
+/// store.send(Send, no_change);
+/// assert_eq!(store.advance_to_next_timer(), Some(Duration::from_secs(4)));
+/// store.recv(Recv, |state| state.n = 3);
+/// assert_eq!(store.advance_to_next_timer(), None);
 /// ```
 pub trait TestClock {
+    /// Moves the clock forward by `duration`; running every timer that becomes due.
     fn advance(&mut self, duration: Duration);
+
+    /// Moves the clock forward to the soonest pending timer and runs it.
+    ///
+    /// Returns how far the clock moved, or `None` if no timers were pending.
+    ///
+    /// The default implementation knows of no timers; so it never moves the clock and
+    /// always returns `None`. Implementations that can find their soonest timer should
+    /// override it, as [`TestStore`][`crate::TestStore`] does.
+    fn advance_to_next_timer(&mut self) -> Option<Duration> {
+        None
+    }
+
+    /// Runs pending timers, soonest first, until none remain or `limit` of them have run;
+    /// the `limit` guards against timers that never end, such as an [`every`] interval.
+    ///
+    /// Returns how far the clock moved in total.
+    ///
+    /// [`every`]: `crate::effects::Scheduler::every`
+    fn run_all_timers(&mut self, limit: usize) -> Duration {
+        (0..limit).map_while(|_| self.advance_to_next_timer()).sum()
+    }
 }
@@ -0,0 +1,224 @@
+use std::fmt::Debug;
+
+/// The number of unchanged lines shown around each change.
+const CONTEXT: usize = 3;
+
+/// Panics, with a line-level diff of their [`Debug`] output, if `expected` and `actual`
+/// are not equal.
+#[track_caller]
+pub(crate) fn assert_same<T: Debug + PartialEq>(what: &str, expected: &T, actual: &T) {
+    if expected != actual {
+        panic!("{}", mismatch(what, expected, actual));
+    }
+}
+
+/// Describes how `actual` differs from `expected`.
+pub(crate) fn mismatch<T: Debug>(what: &str, expected: &T, actual: &T) -> String {
+    format!(
+        "{what} did not match; expected (-) actual (+):\n{}",
+        diff(&format!("{expected:#?}"), &format!("{actual:#?}"))
+    )
+}
+
+/// A line-level diff of `old` and `new`; with `-` marking the lines only in `old`,
+/// `+` those only in `new` and runs of unchanged lines elided.
+pub(crate) fn diff(old: &str, new: &str) -> String {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    // only the lines between their common prefix and suffix need to be searched
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let (rest, remaining) = (&old[prefix..], &new[prefix..]);
+    let suffix = rest
+        .iter()
+        .rev()
+        .zip(remaining.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let removed = &rest[..rest.len() - suffix];
+    let added = &remaining[..remaining.len() - suffix];
+
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    lines.extend(old[..prefix].iter().map(|line| (' ', *line)));
+    match edits(removed, added) {
+        Some(edits) => lines.extend(edits),
+        None => {
+            // too different to search; shown as replaced wholesale
+            lines.extend(removed.iter().map(|line| ('-', *line)));
+            lines.extend(added.iter().map(|line| ('+', *line)));
+        }
+    }
+    lines.extend(rest[rest.len() - suffix..].iter().map(|line| (' ', *line)));
+
+    let changed = |index: usize| {
+        let nearby = index.saturating_sub(CONTEXT)..(index + CONTEXT + 1).min(lines.len());
+        lines[nearby].iter().any(|(marker, _)| *marker != ' ')
+    };
+
+    let mut output = String::new();
+    let mut elided = false;
+    for (index, (marker, line)) in lines.iter().enumerate() {
+        if changed(index) {
+            output.push_str(&format!("{marker} {line}\n"));
+            elided = false;
+        } else if !elided {
+            output.push_str("  …\n");
+            elided = true;
+        }
+    }
+
+    output
+}
+
+/// The most lines that [`edits`] will add or remove; bounding the memory it uses to
+/// `O(MAX_EDITS²)`, however long the lines being compared.
+const MAX_EDITS: usize = 1000;
+
+/// A shortest edit script from `old` to `new`, found with Myers’ algorithm; or `None`
+/// if it would need more than [`MAX_EDITS`] edits.
+fn edits<'a>(old: &[&'a str], new: &[&'a str]) -> Option<Vec<(char, &'a str)>> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let offset = MAX_EDITS as isize + 1;
+
+    // the furthest `x` reached along each diagonal `k = x - y`
+    let mut furthest = vec![0isize; 2 * MAX_EDITS + 3];
+    // `furthest` before each edit `d`; for the diagonals `-d - 1..=d + 1`
+    let mut trace = Vec::new();
+
+    // the diagonal that the `d`th edit onto diagonal `k` was made from, and its furthest `x`
+    let from = |before: &[isize], d: isize, k: isize| {
+        let at = |k: isize| before[(k + d + 1) as usize];
+        match k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            true => (k + 1, at(k + 1)),  // an addition
+            false => (k - 1, at(k - 1)), // a removal
+        }
+    };
+
+    'search: for d in 0..=MAX_EDITS as isize {
+        let diagonals = (offset - d - 1) as usize..=(offset + d + 1) as usize;
+        trace.push(furthest[diagonals].to_vec());
+
+        for k in (-d..=d).step_by(2) {
+            let mut x = match from(&trace[d as usize], d, k) {
+                (previous, x) if previous > k => x,
+                (_, x) => x + 1,
+            };
+
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                (x, y) = (x + 1, y + 1);
+            }
+
+            furthest[(offset + k) as usize] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+
+        if d == MAX_EDITS as isize {
+            return None;
+        }
+    }
+
+    // walking back from the end of both; through the edit that reached each point
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    let (mut x, mut y) = (n, m);
+    for (d, before) in trace.iter().enumerate().skip(1).rev() {
+        let (previous, previous_x) = from(before, d as isize, x - y);
+        let previous_y = previous_x - previous;
+
+        let (edit, after) = match previous > x - y {
+            true => (('+', new[previous_y as usize]), previous_x),
+            false => (('-', old[previous_x as usize]), previous_x + 1),
+        };
+
+        while x > after {
+            lines.push((' ', old[x as usize - 1]));
+            (x, y) = (x - 1, y - 1);
+        }
+
+        lines.push(edit);
+        (x, y) = (previous_x, previous_y);
+    }
+
+    lines.extend(old[..x as usize].iter().rev().map(|line| (' ', *line)));
+    lines.reverse();
+    Some(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj";
+        let new = "a\nb\nc\nd\ne\nF\ng\nh\ni\nj\nk";
+
+        #[rustfmt::skip]
+        assert_eq!(diff(old, new), [
+            "  …",
+            "  c", "  d", "  e",
+            "- f",
+            "+ F",
+            "  g", "  h", "  i",
+            "  j",
+            "+ k",
+            "",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn test_shortest_edits() {
+        // a small linear congruential generator; for lines from a small alphabet
+        let mut seed = 1u32;
+        let mut lines = |len| {
+            let mut lines = Vec::new();
+            for _ in 0..len {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                lines.push(["a", "b", "c"][(seed >> 16) as usize % 3]);
+            }
+            lines
+        };
+
+        for _ in 0..200 {
+            let (old, new) = (lines(12), lines(9));
+            let edits = edits(&old, &new).unwrap();
+
+            let without = |edit| {
+                let lines = edits.iter().filter(|(marker, _)| *marker != edit);
+                lines.map(|(_, line)| *line).collect::<Vec<_>>()
+            };
+            assert_eq!(without('+'), old);
+            assert_eq!(without('-'), new);
+
+            // the length of the longest common subsequence of `old[i..]` and `new[j..]`
+            let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+            for i in (0..old.len()).rev() {
+                for j in (0..new.len()).rev() {
+                    common[i][j] = match old[i] == new[j] {
+                        true => common[i + 1][j + 1] + 1,
+                        false => common[i + 1][j].max(common[i][j + 1]),
+                    };
+                }
+            }
+
+            let unchanged = edits.iter().filter(|(marker, _)| *marker == ' ').count();
+            assert_eq!(unchanged, common[0][0]);
+        }
+    }
+
+    #[test]
+    fn test_large_diffs() {
+        let old = (0..20_000).map(|n| format!("{n}\n")).collect::<String>();
+        let new = old.replacen("10000\n", "changed\n", 1);
+        assert_eq!(diff(&old, &new).lines().count(), 1 + 3 + 2 + 3 + 1);
+
+        // more than `MAX_EDITS` apart
+        let new = (0..20_000).map(|n| format!("+{n}\n")).collect::<String>();
+        let diff = diff(&old, &new);
+        let count = |marker| diff.lines().filter(|line| line.starts_with(marker)).count();
+        assert_eq!((count("- "), count("+ ")), (20_000, 20_000));
+    }
+}
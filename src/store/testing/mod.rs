@@ -6,17 +6,23 @@ use std::time::{Duration, Instant};
 
 use futures::executor::{LocalPool, LocalSpawner};
 use futures::stream::iter;
-use futures::task::LocalSpawnExt;
 use futures::{pin_mut, Stream, StreamExt};
 
 pub use clock::TestClock;
+use diff::{assert_same, diff, mismatch};
+use transcript::Transcript;
 
-use crate::dependencies::{guard::Guard, Dependency};
-use crate::effects::{scheduler::Reactor, Delay, Effects, Scheduler};
+use crate::dependencies::guard::{Guard, Registered};
+use crate::dependencies::{Dependency, Tuple};
+use crate::effects::{scheduler::Reactor, Delay, Effects, Scheduler, Tasks};
 use crate::reducer::Reducer;
+use crate::store::middleware::{reduce, Middleware};
+use crate::store::recording::Recording;
 use crate::Task;
 
 mod clock;
+mod diff;
+mod transcript;
 
 #[doc = include_str!("README.md")]
 pub struct TestStore<State: Reducer>
@@ -25,6 +31,11 @@ where
 {
     state: Option<State>, // `Option` so that `into_inner` does not break `Drop`
     pool: LocalPool,
+    middleware: Vec<Box<dyn Middleware<State>>>,
+    exhaustivity: Exhaustivity,
+    check_effects: bool,
+    transcript: Option<Transcript<State>>,
+    dependencies: Registered,
 
     // external polling
     inner: Rc<RefCell<Inner<<State as Reducer>::Action>>>,
@@ -47,6 +58,33 @@ where
 {
     #[track_caller]
     fn drop(&mut self) {
+        if std::thread::panicking() {
+            return; // a failed assertion has already been reported
+        }
+
+        if let Exhaustivity::Off { show_skipped } = self.exhaustivity {
+            let skipped = self
+                .inner
+                .borrow_mut()
+                .actions
+                .drain(..)
+                .collect::<Vec<_>>();
+            if show_skipped && !skipped.is_empty() {
+                eprintln!("skipped {} received action(s): {skipped:#?}", skipped.len());
+            }
+
+            if self.check_effects {
+                self.assert_no_pending_effects(); // not skipped, even when not exhaustive
+            } else if show_skipped {
+                let in_flight = self.in_flight_effects();
+                if in_flight > 0 {
+                    eprintln!("skipped {in_flight} in-flight effect(s)");
+                }
+            }
+
+            return;
+        }
+
         assert!(
             self.inner.borrow().actions.is_empty(),
             "one or more extra actions were not tested for: {:#?}",
@@ -56,6 +94,10 @@ where
                 .drain(..)
                 .collect::<Vec<_>>()
         );
+
+        if self.check_effects {
+            self.assert_no_pending_effects();
+        }
     }
 }
 
@@ -64,6 +106,7 @@ where
     <State as Reducer>::Action: Debug,
 {
     fn advance(&mut self, duration: Duration) {
+        let _dependencies = self.dependencies.enter();
         let mut inner = self.inner.borrow_mut();
         let now = inner.now + duration;
         inner.now = now;
@@ -75,11 +118,20 @@ where
             self.pool.run_until_stalled();
             timer.poll(now);
 
-            if self.pool.try_run_one() == false {
+            if !self.pool.try_run_one() {
                 break;
             }
         }
     }
+
+    fn advance_to_next_timer(&mut self) -> Option<Duration> {
+        self.advance(Duration::ZERO); // register the delays of any newly started tasks
+
+        let next = self.pending_delays().first().copied()?;
+        self.advance(next);
+
+        Some(next)
+    }
 }
 
 impl<State: Reducer> TestStore<State>
@@ -103,9 +155,209 @@ where
             state: Some(state),
             inner: Inner::new(spawner),
             reactor: Guard::new(Reactor::new()),
+            middleware: Vec::new(),
+            exhaustivity: Exhaustivity::default(),
+            check_effects: false,
+            transcript: None,
+            dependencies: Registered::default(),
             pool,
         }
     }
+
+    /// Registers `value` as a [`Dependency`] of the `Store`.
+    ///
+    /// It is current whenever the `Store` reduces an action, or runs its tasks; so a
+    /// [`DependencyDefault`] can be replaced for the whole test.
+    ///
+    /// ```rust
+    /// # use composable::*;
+    /// # use composable::dependencies::{Dependency, DependencyDefault};
+    /// #[derive(Default)]
+    /// struct Locale(&'static str);
+    ///
+    /// impl DependencyDefault for Locale {}
+    /// #
+    /// # #[derive(Clone, Debug, Default, PartialEq)]
+    /// # struct State { greeting: &'static str }
+    /// #
+    /// # #[derive(Clone, Debug, PartialEq)]
+    /// # enum Action { Greet }
+    /// #
+    /// # impl Reducer for State {
+    /// #     type Action = Action;
+    /// #     type Output = Self;
+    /// #
+    /// #     fn reduce(&mut self, _action: Action, _send: impl Effects<Action>) {
+    /// #         self.greeting = match Dependency::<Locale>::new().0 {
+    /// #             "fr" => "bonjour",
+    /// #             _ => "hello",
+    /// #         };
+    /// #     }
+    /// # }
+    ///
+    /// let mut store = TestStore::<State>::default().with_dependency(Locale("fr"));
+    /// store.send(Action::Greet, |state| state.greeting = "bonjour");
+    /// ```
+    ///
+    /// [`DependencyDefault`]: crate::dependencies::DependencyDefault
+    pub fn with_dependency<T: 'static>(mut self, value: T) -> Self {
+        self.dependencies.insert(value);
+        self
+    }
+
+    /// Registers a tuple of values as [`Dependency`]s of the `Store`.
+    ///
+    /// See: [`with_dependency`][`TestStore::with_dependency`]
+    pub fn with_dependencies<T: Tuple>(mut self, values: T) -> Self {
+        values.register(&mut self.dependencies);
+        self
+    }
+
+    /// Sets how strictly the `Store` checks the actions it receives and the state
+    /// changes they make.
+    ///
+    /// ```rust
+    /// # use composable::*;
+    /// # #[derive(Clone, Debug, Default, PartialEq)]
+    /// # struct State { user: Option<String>, loading: bool }
+    /// #
+    /// # #[derive(Clone, Debug, PartialEq)]
+    /// # enum Action { Login, LoggedIn(String), Logout }
+    /// #
+    /// # impl Reducer for State {
+    /// #     type Action = Action;
+    /// #     type Output = Self;
+    /// #
+    /// #     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+    /// #         match action {
+    /// #             Action::Login => {
+    /// #                 self.loading = true;
+    /// #                 send.future(async { Some(Action::LoggedIn("ada".into())) });
+    /// #             }
+    /// #             Action::LoggedIn(user) => (self.user, self.loading) = (Some(user), false),
+    /// #             Action::Logout => self.user = None,
+    /// #         }
+    /// #     }
+    /// # }
+    /// #
+    /// let mut store = TestStore::<State>::default();
+    /// store.set_exhaustivity(Exhaustivity::Off { show_skipped: true });
+    ///
+    /// store.send(Action::Login, |_| {}); // ignores the change to `loading`
+    /// store.wait();
+    /// store.send(Action::Logout, |state| state.user = None); // and `LoggedIn`
+    /// ```
+    pub fn set_exhaustivity(&mut self, exhaustivity: Exhaustivity) {
+        self.exhaustivity = exhaustivity;
+    }
+
+    /// Sets whether dropping the `Store` also fails if any of its effects are still in
+    /// flight; such as a detached [`every`][`crate::effects::Scheduler::every`] timer.
+    ///
+    /// The check is made whatever the [`Exhaustivity`]; in-flight effects are only
+    /// skipped explicitly, with [`skip_in_flight_effects`][`TestStore::skip_in_flight_effects`].
+    ///
+    /// See: [`assert_no_pending_effects`][`TestStore::assert_no_pending_effects`]
+    pub fn set_check_effects_on_drop(&mut self, check: bool) {
+        self.check_effects = check;
+    }
+
+    /// The number of [`Task`]s that are still running; detached or not.
+    ///
+    /// A task that was cancelled, such as the previous task of a
+    /// [`debounce`][`crate::effects::Scheduler::debounce`], is no longer counted.
+    ///
+    /// The tasks are run until they stall first, without advancing the clock; so that
+    /// those cancelled are dropped, and those just started have begun.
+    pub fn in_flight_effects(&mut self) -> usize {
+        self.run_until_stalled();
+        self.inner.borrow().tasks.len()
+    }
+
+    /// How long until each of the `Store`’s pending delays is ready; soonest first.
+    ///
+    /// The delays of cancelled tasks are not included, while those of tasks that have
+    /// just started are; as with [`in_flight_effects`][`TestStore::in_flight_effects`].
+    pub fn pending_delays(&mut self) -> Vec<Duration> {
+        self.run_until_stalled();
+
+        let now = self.inner.borrow().now;
+        let pending = Dependency::<Reactor>::new().map(|reactor| reactor.pending());
+
+        pending
+            .unwrap_or_default()
+            .into_iter()
+            .map(|instant| instant.saturating_duration_since(now))
+            .collect()
+    }
+
+    /// Asserts that none of the `Store`’s effects are still in flight, and that none of
+    /// its delays are pending.
+    #[track_caller]
+    pub fn assert_no_pending_effects(&mut self) {
+        let (in_flight, pending) = (self.in_flight_effects(), self.pending_delays());
+
+        assert!(
+            in_flight == 0 && pending.is_empty(),
+            "{in_flight} effect(s) still in flight, with delays pending in {pending:?}"
+        );
+    }
+
+    /// Starts recording a transcript of each step the `Store` takes: every action that it
+    /// sends or receives, followed by the resulting state’s [`Debug`] output.
+    ///
+    /// With [`step`] and [`transcript`], a whole sequence can then be checked by a single
+    /// snapshot; rather than by a closure for each step.
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use composable::*;
+    /// # #[derive(Clone, Debug, Default, PartialEq)]
+    /// # struct State { loading: bool }
+    /// #
+    /// # #[derive(Clone, Debug, PartialEq)]
+    /// # enum Action { Load, Loaded }
+    /// #
+    /// # impl Reducer for State {
+    /// #     type Action = Action;
+    /// #     type Output = Self;
+    /// #
+    /// #     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+    /// #         self.loading = action == Action::Load;
+    /// #         if self.loading {
+    /// #             send.after(Duration::from_secs(1), Action::Loaded).detach();
+    /// #         }
+    /// #     }
+    /// # }
+    /// #
+    /// let mut store = TestStore::<State>::default();
+    /// store.record_transcript();
+    /// store.step(Action::Load);
+    /// store.advance(Duration::from_secs(1));
+    ///
+    /// // usually `insta::assert_snapshot!(store.transcript())`
+    /// assert_eq!(store.transcript(), [
+    ///     "send: Load", "State {", "    loading: true,", "}", "",
+    ///     "recv: Loaded", "State {", "    loading: false,", "}", "",
+    /// ].join("\n"));
+    /// ```
+    ///
+    /// [`step`]: TestStore::step
+    /// [`transcript`]: TestStore::transcript
+    pub fn record_transcript(&mut self)
+    where
+        State: Debug,
+    {
+        self.transcript = Some(Transcript::new());
+    }
+
+    /// Adds a [`Middleware`] to be called around every action that the `Store` reduces.
+    ///
+    /// See: [`Store::add_middleware`][`crate::Store::add_middleware`]
+    pub fn add_middleware(&mut self, middleware: impl Middleware<State> + 'static) {
+        self.middleware.push(Box::new(middleware))
+    }
+
     /// Calls the `Store`’s [`Reducer`][`crate::Reducer`] with `action` and asserts the
     /// expected state changes.
     #[track_caller]
@@ -114,24 +366,66 @@ where
         State: Clone + Debug + PartialEq,
         <State as Reducer>::Action: 'static,
     {
-        let mut expected = self.state.clone();
-        assert(expected.as_mut().unwrap());
+        let _dependencies = self.dependencies.enter();
+        match self.exhaustivity {
+            Exhaustivity::On => assert!(
+                self.inner.borrow().actions.is_empty(),
+                "an extra action was received: {:#?}",
+                self.inner
+                    .borrow_mut()
+                    .actions
+                    .drain(..)
+                    .collect::<Vec<_>>()
+            ),
+            Exhaustivity::Off { .. } => self.skip_received_actions(),
+        }
+
+        let previous = self.state.clone();
+        self.reduce_step("send", action);
+        self.assert_state(previous, assert);
+    }
+
+    /// Calls the `Store`’s [`Reducer`][`crate::Reducer`] with `action`, recording the
+    /// state changes in its transcript rather than asserting them.
+    ///
+    /// Any actions the `Store` has already received are reduced, and recorded, first.
+    ///
+    /// # Panics
+    ///
+    /// If the `Store` is not [recording a transcript][`TestStore::record_transcript`].
+    #[track_caller]
+    pub fn step(&mut self, action: <State as Reducer>::Action)
+    where
+        <State as Reducer>::Action: 'static,
+    {
+        assert!(
+            self.transcript.is_some(),
+            "TestStore::step requires TestStore::record_transcript"
+        );
 
+        let _dependencies = self.dependencies.enter();
+        self.skip_received_actions();
+        self.reduce_step("send", action);
+    }
+
+    /// Reduces any actions that the `Store` has received, and returns the transcript
+    /// recorded since it was last taken; for a single snapshot assertion.
+    ///
+    /// # Panics
+    ///
+    /// If the `Store` is not [recording a transcript][`TestStore::record_transcript`].
+    #[track_caller]
+    pub fn transcript(&mut self) -> String
+    where
+        <State as Reducer>::Action: 'static,
+    {
         assert!(
-            self.inner.borrow().actions.is_empty(),
-            "an extra action was received: {:#?}",
-            self.inner
-                .borrow_mut()
-                .actions
-                .drain(..)
-                .collect::<Vec<_>>()
+            self.transcript.is_some(),
+            "TestStore::transcript requires TestStore::record_transcript"
         );
 
-        self.state
-            .as_mut()
-            .unwrap()
-            .reduce(action, self.inner.clone());
-        assert_eq!(self.state, expected);
+        self.skip_received_actions();
+        self.transcript.as_mut().map(Transcript::take).unwrap()
     }
 
     /// Checks that the `Store`’s [`Reducer`][`crate::Reducer`] was called with `action`
@@ -142,22 +436,322 @@ where
         State: Clone + Debug + PartialEq,
         <State as Reducer>::Action: Debug + PartialEq + 'static,
     {
+        let _dependencies = self.dependencies.enter();
+        let received = match self.receive(|received| *received == action, &format!("{action:?}")) {
+            Ok(received) => received,
+            Err(received) => panic!("{}", mismatch("the received action", &action, &received)),
+        };
+
+        self.reduce_received(received, assert);
+    }
+
+    /// Checks that the `Store`’s [`Reducer`][`crate::Reducer`] was called with an action
+    /// that `matches`, and asserts the expected state changes.
+    ///
+    /// Useful for actions that carry timestamps, ids or large payloads.
+    ///
+    /// ```rust
+    /// # use std::time::{Duration, SystemTime};
+    /// # use composable::*;
+    /// # #[derive(Clone, Debug, Default, PartialEq)]
+    /// # struct State { saving: bool }
+    /// #
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Action {
+    ///     Save,
+    ///     Saved { at: SystemTime },
+    /// }
+    /// #
+    /// # impl Reducer for State {
+    /// #     type Action = Action;
+    /// #     type Output = Self;
+    /// #
+    /// #     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+    /// #         match action {
+    /// #             Action::Save => {
+    /// #                 self.saving = true;
+    /// #                 send.future(async { Some(Action::Saved { at: SystemTime::now() }) });
+    /// #             }
+    /// #             Action::Saved { .. } => self.saving = false,
+    /// #         }
+    /// #     }
+    /// # }
+    ///
+    /// let mut store = TestStore::<State>::default();
+    /// store.send(Action::Save, |state| state.saving = true);
+    /// store.wait();
+    ///
+    /// store.recv_matching(
+    ///     |action| matches!(action, Action::Saved { .. }),
+    ///     |state| state.saving = false,
+    /// );
+    /// ```
+    #[track_caller]
+    pub fn recv_matching(
+        &mut self,
+        matches: impl Fn(&<State as Reducer>::Action) -> bool,
+        assert: impl FnOnce(&mut State),
+    ) where
+        State: Clone + Debug + PartialEq,
+        <State as Reducer>::Action: 'static,
+    {
+        let _dependencies = self.dependencies.enter();
+        let received = match self.receive(&matches, "an action matching the predicate") {
+            Ok(received) => received,
+            Err(received) => panic!("the received action did not match: {received:#?}"),
+        };
+
+        self.reduce_received(received, assert);
+    }
+
+    /// Checks that the `Store`’s [`Reducer`][`crate::Reducer`] was called with an action
+    /// of the variant that `case` extracts the contents of, and asserts the expected state
+    /// changes; which may depend upon those contents.
+    ///
+    /// `case` returns `None` for any other variant; even one with contents of the same type.
+    ///
+    /// ```rust
+    /// # use composable::*;
+    /// # #[derive(Clone, Debug, Default, PartialEq)]
+    /// # struct State { items: Vec<String>, drafts: Vec<String> }
+    /// #
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Action {
+    ///     Fetch,
+    ///     Fetched(Vec<String>),
+    ///     Drafted(Vec<String>),
+    /// }
+    /// #
+    /// # impl Reducer for State {
+    /// #     type Action = Action;
+    /// #     type Output = Self;
+    /// #
+    /// #     fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+    /// #         match action {
+    /// #             Action::Fetch => send.future(async { Some(Action::Fetched(vec![])) }),
+    /// #             Action::Fetched(items) => self.items = items,
+    /// #             Action::Drafted(drafts) => self.drafts = drafts,
+    /// #         }
+    /// #     }
+    /// # }
+    ///
+    /// let fetched = |action| match action {
+    ///     Action::Fetched(items) => Some(items),
+    ///     _ => None,
+    /// };
+    ///
+    /// let mut store = TestStore::<State>::default();
+    /// store.send(Action::Fetch, |_| {});
+    /// store.wait();
+    /// store.recv_case(fetched, |state, items| state.items = items);
+    /// ```
+    #[track_caller]
+    pub fn recv_case<Case>(
+        &mut self,
+        case: impl Fn(<State as Reducer>::Action) -> Option<Case>,
+        assert: impl FnOnce(&mut State, Case),
+    ) where
+        State: Clone + Debug + PartialEq,
+        <State as Reducer>::Action: Clone + 'static,
+    {
+        let _dependencies = self.dependencies.enter();
+        let expected = "an action of the expected case";
+
+        let received = match self.receive(|received| case(received.clone()).is_some(), expected) {
+            Ok(received) => received,
+            Err(received) => panic!("the received action was not the expected case: {received:#?}"),
+        };
+
+        let contents = case(received.clone()).unwrap();
+        self.reduce_received(received, |state| assert(state, contents));
+    }
+
+    /// The next received action, if it `matches`; or else, when not exhaustive, the first
+    /// that does after reducing those before it — as a `Store` would have.
+    ///
+    /// Returns the unmatched action as an `Err`, when exhaustive.
+    #[track_caller]
+    fn receive(
+        &mut self,
+        matches: impl Fn(&<State as Reducer>::Action) -> bool,
+        expected: &str,
+    ) -> Result<<State as Reducer>::Action, <State as Reducer>::Action>
+    where
+        <State as Reducer>::Action: 'static,
+    {
+        loop {
+            let received = self.inner.borrow_mut().actions.pop_front();
+            let Some(received) = received else {
+                panic!("{expected} was not received");
+            };
+
+            match self.exhaustivity {
+                _ if matches(&received) => return Ok(received),
+                Exhaustivity::On => return Err(received),
+                Exhaustivity::Off { .. } => {
+                    self.inner.borrow_mut().actions.push_front(received);
+                    self.skip_received_action();
+                }
+            }
+        }
+    }
+
+    #[track_caller]
+    fn reduce_received(
+        &mut self,
+        action: <State as Reducer>::Action,
+        assert: impl FnOnce(&mut State),
+    ) where
+        State: Clone + Debug + PartialEq,
+        <State as Reducer>::Action: 'static,
+    {
+        let previous = self.state.clone();
+        self.reduce_step("recv", action);
+        self.assert_state(previous, assert);
+    }
+
+    /// Reduces `action`; recording it, and the resulting state, as a `step` of the
+    /// transcript if one is being recorded.
+    fn reduce_step(&mut self, step: &str, action: <State as Reducer>::Action)
+    where
+        <State as Reducer>::Action: 'static,
+    {
+        let described = self.transcript.is_some().then(|| format!("{action:?}"));
+
+        let state = self.state.as_mut().unwrap();
+        reduce(&mut self.middleware, state, action, self.inner.clone());
+
+        if let (Some(transcript), Some(action)) = (&mut self.transcript, described) {
+            transcript.record(step, &action, self.state.as_ref().unwrap());
+        }
+    }
+
+    /// Reduces every action that the `Store` has received, and any that they send,
+    /// without asserting anything about them.
+    pub fn skip_received_actions(&mut self)
+    where
+        <State as Reducer>::Action: 'static,
+    {
+        let _dependencies = self.dependencies.enter();
+        while !self.inner.borrow().actions.is_empty() {
+            self.skip_received_action();
+        }
+    }
+
+    /// Cancels every task that is still running, such as futures and timers, so that
+    /// the actions they would have sent are never received.
+    pub fn skip_in_flight_effects(&mut self) {
+        let _dependencies = self.dependencies.enter();
+        let tasks = self.inner.borrow().tasks.clone();
+        let skipped = tasks.len();
+        tasks.abort();
+        self.pool.run_until_stalled(); // so that the aborted tasks are dropped
+
+        if let Exhaustivity::Off { show_skipped: true } = self.exhaustivity {
+            eprintln!("skipped {skipped} in-flight effect(s)");
+        }
+    }
+
+    fn skip_received_action(&mut self)
+    where
+        <State as Reducer>::Action: 'static,
+    {
+        let Some(action) = self.inner.borrow_mut().actions.pop_front() else {
+            return;
+        };
+
+        if let Exhaustivity::Off { show_skipped: true } = self.exhaustivity {
+            eprintln!("skipped received action: {action:#?}");
+        }
+
+        self.reduce_step("recv", action);
+    }
+
+    fn state(&self) -> &State {
+        self.state.as_ref().unwrap()
+    }
+
+    /// Asserts that `assert` describes the change from the `previous` state.
+    ///
+    /// When not exhaustive, `assert` is applied to the new state instead; so that only
+    /// the values that it sets are checked.
+    #[track_caller]
+    fn assert_state(&self, previous: Option<State>, assert: impl FnOnce(&mut State))
+    where
+        State: Clone + Debug + PartialEq,
+    {
+        match self.exhaustivity {
+            Exhaustivity::On => {
+                let mut expected = previous;
+                assert(expected.as_mut().unwrap());
+                assert_same("the state", expected.as_ref().unwrap(), self.state());
+            }
+            Exhaustivity::Off { show_skipped } => {
+                let mut expected = self.state.clone();
+                assert(expected.as_mut().unwrap());
+                assert_same("the state", expected.as_ref().unwrap(), self.state());
+
+                if show_skipped && self.state != previous {
+                    eprintln!(
+                        "state changes were not exhaustively asserted; previous (-) current (+):\n{}",
+                        diff(&format!("{:#?}", previous.unwrap()), &format!("{:#?}", self.state()))
+                    );
+                }
+            }
+        }
+    }
+
+    /// Replays a [`Recording`] of another `Store` and asserts the expected final state.
+    ///
+    /// The actions that began each batch are reduced in order; with the `Store`’s clock
+    /// advanced to match the recording. The actions they send with
+    /// [`Effects::action`][`crate::effects::Effects::action`] are reduced after each,
+    /// while their tasks are not started — the actions those sent are already part of
+    /// the `recording`.
+    ///
+    /// See the [`recording`][`crate::recording`] module for more.
+    #[track_caller]
+    pub fn replay(
+        &mut self,
+        recording: &Recording<<State as Reducer>::Action>,
+        assert: impl FnOnce(&mut State),
+    ) where
+        State: Clone + Debug + PartialEq,
+        <State as Reducer>::Action: Clone + 'static,
+    {
+        let _dependencies = self.dependencies.enter();
         let mut expected = self.state.clone();
         assert(expected.as_mut().unwrap());
 
-        let received = self
-            .inner
-            .borrow_mut()
-            .actions
-            .pop_front()
-            .expect("no action received");
-        assert_eq!(received, action);
+        let mut inner = self.inner.borrow_mut();
+        let start = inner.now;
+        inner.replaying = true;
+        drop(inner);
+
+        let next = |inner: &Rc<RefCell<Inner<_>>>| inner.borrow_mut().actions.pop_front();
 
-        self.state
-            .as_mut()
-            .unwrap()
-            .reduce(action, self.inner.clone());
-        assert_eq!(self.state, expected);
+        for entry in recording.sent() {
+            let now = start + entry.at;
+            let mut inner = self.inner.borrow_mut();
+            inner.now = inner.now.max(now);
+            drop(inner);
+
+            let state = self.state.as_mut().unwrap();
+            reduce(
+                &mut self.middleware,
+                state,
+                entry.action.clone(),
+                self.inner.clone(),
+            );
+
+            while let Some(action) = next(&self.inner) {
+                let state = self.state.as_mut().unwrap();
+                reduce(&mut self.middleware, state, action, self.inner.clone());
+            }
+        }
+
+        self.inner.borrow_mut().replaying = false;
+        assert_same("the state", expected.as_ref().unwrap(), self.state());
     }
 
     /// Waits until all scheduled tasks have completed.
@@ -175,9 +769,15 @@ where
     /// [timeout]: https://docs.rs/ntest/latest/ntest/attr.timeout.html
     /// [max_time]: https://docs.rs/divan/0.1.14/divan/attr.bench.html#max_time
     pub fn wait(&mut self) {
+        let _dependencies = self.dependencies.enter();
         self.pool.run()
     }
 
+    fn run_until_stalled(&mut self) {
+        let _dependencies = self.dependencies.enter();
+        self.pool.run_until_stalled()
+    }
+
     /// Consumes the `Store` and returns its current `state` value.
     pub fn into_inner(mut self) -> <State as Reducer>::Output
     where
@@ -187,10 +787,33 @@ where
     }
 }
 
+/// How strictly a [`TestStore`] checks the actions it receives and the state changes
+/// they make.
+///
+/// See: [`TestStore::set_exhaustivity`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Exhaustivity {
+    /// Every state change must be asserted, and every action received must be checked
+    /// with [`recv`][`TestStore::recv`], or the test fails.
+    #[default]
+    On,
+    /// Only the state that is asserted is checked. Any received actions that are not
+    /// checked are reduced, as a [`Store`][`crate::Store`] would, but otherwise ignored.
+    ///
+    /// Useful for integration-style tests of large, composed, reducers; where only a
+    /// few of the changes are of interest.
+    Off {
+        /// Logs the skipped actions and any state changes that may not have been asserted.
+        show_skipped: bool,
+    },
+}
+
 struct Inner<Action> {
     actions: VecDeque<Action>,
     spawner: LocalSpawner,
+    tasks: Rc<Tasks>,
     now: Instant,
+    replaying: bool,
 }
 
 #[doc(hidden)]
@@ -205,19 +828,21 @@ where
     }
 
     fn task<S: Stream<Item = Action> + 'static>(&self, stream: S) -> Task {
+        if self.borrow().replaying {
+            return Task::none(); // their actions are already part of the `Recording`
+        }
+
         let effects = self.clone();
-        let spawner = self.borrow().spawner.clone();
+        let inner = self.borrow();
 
-        let handle = spawner
-            .spawn_local_with_handle(async move {
-                pin_mut!(stream);
-                while let Some(action) = stream.next().await {
-                    effects.action(action);
-                }
-            })
-            .ok();
+        let task = inner.tasks.spawn(&inner.spawner, async move {
+            pin_mut!(stream);
+            while let Some(action) = stream.next().await {
+                effects.action(action);
+            }
+        });
 
-        Task { handle, when: None }
+        task.unwrap_or_else(Task::none)
     }
 }
 
@@ -255,8 +880,352 @@ impl<Action> Inner<Action> {
     fn new(spawner: LocalSpawner) -> Rc<RefCell<Self>> {
         Rc::new(RefCell::new(Self {
             actions: Default::default(),
-            now: Instant::now().into(),
+            now: Instant::now(),
+            replaying: false,
             spawner,
+            tasks: Default::default(),
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::dependencies::DependencyDefault;
+    use crate::{Effects, Interval, Task};
+
+    use super::*;
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct State {
+        characters: Vec<char>,
+        count: usize,
+    }
+
+    /// Added to `count` by `+`, and sent by `=` from a future.
+    #[derive(Default)]
+    struct Offset(usize);
+
+    impl DependencyDefault for Offset {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Action {
+        Char(char),
+        Counted(usize),
+        Length(usize),
+    }
+
+    fn counted(action: Action) -> Option<usize> {
+        match action {
+            Action::Counted(count) => Some(count),
+            _ => None,
+        }
+    }
+
+    impl Reducer for State {
+        type Action = Action;
+        type Output = Self;
+
+        fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+            let ch = match action {
+                Action::Char(ch) => ch,
+                Action::Counted(_) | Action::Length(_) => return,
+            };
+
+            self.characters.push(ch);
+            self.count += 1;
+
+            match ch {
+                '1' => "ABC".chars().for_each(|ch| send.action(Action::Char(ch))),
+                'F' => send.future(async { Some(Action::Char('f')) }),
+                'N' => send.action(Action::Counted(self.count)),
+                'L' => send.action(Action::Length(self.characters.len())),
+                '+' => self.count += Dependency::<Offset>::new().0,
+                '=' => send.future(async { Some(Action::Counted(Dependency::<Offset>::new().0)) }),
+                'E' => send
+                    .every(
+                        Interval::Trailing(Duration::from_secs(1)),
+                        Action::Char('e'),
+                    )
+                    .detach(),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_exhaustive() {
+        let mut store = TestStore::<State>::default();
+        store.set_exhaustivity(Exhaustivity::Off {
+            show_skipped: false,
+        });
+
+        use Action::*;
+        store.send(Char('1'), |state| state.count = 1); // `characters` is not asserted
+        store.recv(Char('C'), |state| state.count = 4); // skipping `A` and `B`
+
+        store.send(Char('F'), |state| state.count = 5);
+        store.skip_in_flight_effects();
+        store.wait(); // `f` is never received
+
+        store.send(Char('1'), |_| {}); // its actions are still unreceived when dropped
+        assert_eq!(
+            store.state.as_ref().unwrap().characters,
+            "1ABCF1".chars().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_recv_matching() {
+        let mut store = TestStore::<State>::default();
+
+        use Action::*;
+        store.send(Char('1'), |state| {
+            state.characters.push('1');
+            state.count = 1;
+        });
+
+        let is_char = |action: &Action| matches!(action, Char(_));
+        store.recv_matching(is_char, |state| {
+            state.characters.push('A');
+            state.count = 2;
+        });
+
+        store.skip_received_actions(); // `B` and `C`
+        store.send(Char('N'), |state| {
+            state.characters = "1ABCN".chars().collect();
+            state.count = 5;
+        });
+
+        store.recv_case(counted, |_, count| assert_eq!(count, 5));
+    }
+
+    #[test]
+    #[should_panic(expected = "the received action was not the expected case: Length(\n    1,\n)")]
+    fn test_recv_case_mismatch() {
+        let mut store = TestStore::<State>::default();
+
+        use Action::*;
+        store.send(Char('L'), |state| {
+            state.characters.push('L');
+            state.count = 1;
+        });
+
+        store.recv_case(counted, |_, _| {}); // a `Length`; though it too carries a `usize`
+    }
+
+    #[test]
+    fn test_dependencies() {
+        let mut store = TestStore::<State>::default().with_dependency(Offset(10));
+
+        use Action::*;
+        store.send(Char('+'), |state| {
+            state.characters.push('+');
+            state.count = 11;
+        });
+
+        store.send(Char('='), |state| {
+            state.characters.push('=');
+            state.count = 12;
+        });
+
+        store.wait(); // the future runs with the `Offset` too
+        store.recv(Counted(10), |_| {});
+    }
+
+    #[test]
+    fn test_skip_received_actions() {
+        let mut store = TestStore::<State>::default();
+
+        use Action::*;
+        store.send(Char('1'), |state| {
+            state.characters.push('1');
+            state.count = 1;
+        });
+
+        store.skip_received_actions();
+        store.send(Char('2'), |state| {
+            state.characters = "1ABC2".chars().collect();
+            state.count = 5;
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "\n-     count: 2,\n+     count: 1,\n")]
+    fn test_state_diff() {
+        let mut store = TestStore::<State>::default();
+
+        use Action::*;
+        store.send(Char('2'), |state| {
+            state.characters.push('2');
+            state.count = 2;
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Char('X') was not received")]
+    fn test_non_exhaustive_recv() {
+        let mut store = TestStore::<State>::default();
+        store.set_exhaustivity(Exhaustivity::Off {
+            show_skipped: false,
+        });
+
+        use Action::*;
+        store.send(Char('1'), |_| {});
+        store.recv(Char('X'), |_| {});
+    }
+
+    #[test]
+    fn test_pending_effects() {
+        let mut store = TestStore::<State>::default();
+        store.set_check_effects_on_drop(true);
+
+        use Action::*;
+        store.send(Char('E'), |state| {
+            state.characters.push('E');
+            state.count = 1;
+        });
+        store.advance(Duration::ZERO);
+        assert_eq!(store.in_flight_effects(), 1);
+        assert_eq!(store.pending_delays(), [Duration::from_secs(1)]);
+
+        store.advance(Duration::from_millis(250));
+        assert_eq!(store.pending_delays(), [Duration::from_millis(750)]);
+
+        store.skip_in_flight_effects();
+        assert_eq!(store.in_flight_effects(), 0);
+        assert_eq!(store.pending_delays(), []);
+        store.assert_no_pending_effects();
+    }
+
+    #[test]
+    fn test_cancelled_effects() {
+        /// Debounces each `true` action; sending `false` a second after the last of them.
+        #[derive(Debug, Default)]
+        struct Debouncer(Option<Task>);
+
+        impl Clone for Debouncer {
+            fn clone(&self) -> Self {
+                Debouncer(None)
+            }
+        }
+
+        impl PartialEq for Debouncer {
+            fn eq(&self, _other: &Self) -> bool {
+                true
+            }
+        }
+
+        impl Reducer for Debouncer {
+            type Action = bool;
+            type Output = Self;
+
+            fn reduce(&mut self, debounce: bool, send: impl Effects<bool>) {
+                if debounce {
+                    let interval = Interval::Trailing(Duration::from_secs(1));
+                    send.debounce(false, &mut self.0, interval);
+                }
+            }
+        }
+
+        let mut store = TestStore::<Debouncer>::default();
+        store.set_check_effects_on_drop(true);
+
+        store.send(true, |_| {});
+        assert_eq!(store.in_flight_effects(), 1);
+        assert_eq!(store.pending_delays(), [Duration::from_secs(1)]); // without an `advance`
+
+        store.send(true, |_| {}); // cancels the first
+        assert_eq!(store.in_flight_effects(), 1);
+        assert_eq!(store.pending_delays(), [Duration::from_secs(1)]);
+
+        store.advance(Duration::from_secs(1));
+        store.recv(false, |_| {});
+    }
+
+    #[test]
+    #[should_panic(expected = "1 effect(s) still in flight, with delays pending in [1s]")]
+    fn test_pending_effects_on_drop() {
+        let mut store = TestStore::<State>::default();
+        store.set_check_effects_on_drop(true);
+
+        use Action::*;
+        store.send(Char('E'), |state| {
+            state.characters.push('E');
+            state.count = 1;
+        });
+        store.advance(Duration::ZERO);
+    }
+
+    #[test]
+    #[should_panic(expected = "1 effect(s) still in flight, with delays pending in [1s]")]
+    fn test_pending_effects_on_drop_non_exhaustive() {
+        let mut store = TestStore::<State>::default();
+        store.set_exhaustivity(Exhaustivity::Off {
+            show_skipped: false,
+        });
+        store.set_check_effects_on_drop(true);
+
+        use Action::*;
+        store.send(Char('E'), |_| {});
+    }
+
+    #[test]
+    fn test_test_clock_defaults() {
+        /// A `TestClock` implemented before `advance_to_next_timer` was added.
+        struct Elapsed(Duration);
+
+        impl TestClock for Elapsed {
+            fn advance(&mut self, duration: Duration) {
+                self.0 += duration;
+            }
+        }
+
+        let mut clock = Elapsed(Duration::ZERO);
+        assert_eq!(clock.advance_to_next_timer(), None);
+        assert_eq!(clock.run_all_timers(10), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_run_all_timers() {
+        let mut store = TestStore::<State>::default();
+
+        use Action::*;
+        store.send(Char('E'), |state| {
+            state.characters.push('E');
+            state.count = 1;
+        });
+        assert_eq!(store.advance_to_next_timer(), Some(Duration::from_secs(1)));
+        store.recv(Char('e'), |state| {
+            state.characters.push('e');
+            state.count = 2;
+        });
+
+        assert_eq!(store.run_all_timers(2), Duration::from_secs(2));
+        store.skip_received_actions();
+
+        store.skip_in_flight_effects();
+        assert_eq!(store.advance_to_next_timer(), None);
+        assert_eq!(store.run_all_timers(10), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_transcript() {
+        use insta::assert_snapshot;
+
+        let mut store = TestStore::<State>::default();
+        store.record_transcript();
+
+        use Action::*;
+        store.step(Char('1'));
+        store.step(Char('N'));
+        store.recv(Counted(5), |_| {});
+        store.send(Char('F'), |state| {
+            state.characters.push('F');
+            state.count = 6;
+        });
+        store.wait();
+
+        assert_snapshot!("transcript", store.transcript());
+        assert_eq!(store.transcript(), "");
+    }
+}
@@ -0,0 +1,33 @@
+use std::fmt::{Debug, Write};
+
+/// Each step that a [`TestStore`][`crate::TestStore`] takes; the action it sent or
+/// received, followed by the resulting state.
+pub(crate) struct Transcript<State> {
+    steps: String,
+    describe: fn(&State) -> String,
+}
+
+impl<State: Debug> Transcript<State> {
+    pub(crate) fn new() -> Self {
+        Self {
+            steps: String::new(),
+            describe: |state| format!("{state:#?}"),
+        }
+    }
+}
+
+impl<State> Transcript<State> {
+    pub(crate) fn record(&mut self, step: &str, action: &str, state: &State) {
+        if !self.steps.is_empty() {
+            self.steps.push('\n'); // a blank line between steps
+        }
+
+        let state = (self.describe)(state);
+        writeln!(self.steps, "{step}: {action}\n{state}").unwrap();
+    }
+
+    /// The steps recorded so far; leaving the transcript empty.
+    pub(crate) fn take(&mut self) -> String {
+        std::mem::take(&mut self.steps)
+    }
+}
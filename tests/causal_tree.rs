@@ -0,0 +1,146 @@
+//! The `tracing` spans of a `Store`’s actions form the causal tree of those actions.
+//!
+//! The spans are recorded by the global default subscriber; as `tracing` caches, per
+//! callsite, whether a subscriber is interested in it. This test has a binary of its own
+//! so that no other test can register those callsites first.
+
+use std::sync::{Arc, Mutex};
+
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use tracing_core::span::Current;
+
+use composable::{Effects, LocalStore, Reducer};
+
+#[derive(Clone, Debug, Default)]
+struct State;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Action {
+    Internal(char),
+    External(char),
+}
+
+impl Reducer for State {
+    type Action = Action;
+    type Output = Self;
+
+    fn reduce(&mut self, action: Action, send: impl Effects<Action>) {
+        use Action::*;
+
+        match action {
+            External('1') => "ABCD".chars().for_each(|ch| send.action(Internal(ch))),
+            External('F') => send.future(async { Some(Internal('f')) }),
+            _ => {}
+        }
+    }
+}
+
+/// The name and parent of each span; and the spans entered.
+#[derive(Default)]
+struct Spans {
+    opened: Vec<(&'static Metadata<'static>, Option<Id>)>,
+    entered: Vec<Id>,
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Spans>>);
+
+impl Recorder {
+    /// Each span’s ancestry; sorted.
+    fn paths(&self) -> Vec<String> {
+        let spans = self.0.lock().unwrap();
+        let path = |mut id: Option<Id>| {
+            let mut names = Vec::new();
+            while let Some((metadata, parent)) = id.map(|id| &spans.opened[id_index(&id)]) {
+                names.push(metadata.name());
+                id = parent.clone();
+            }
+
+            names.reverse();
+            names.join(" > ")
+        };
+
+        let mut paths = (1..=spans.opened.len() as u64)
+            .map(|id| path(Some(Id::from_u64(id))))
+            .collect::<Vec<_>>();
+
+        paths.sort();
+        paths
+    }
+}
+
+fn id_index(id: &Id) -> usize {
+    id.into_u64() as usize - 1
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+        let mut spans = self.0.lock().unwrap();
+        let parent = match attributes.is_contextual() {
+            true => spans.entered.last().cloned(),
+            false => attributes.parent().cloned(),
+        };
+
+        spans.opened.push((attributes.metadata(), parent));
+        Id::from_u64(spans.opened.len() as u64)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        self.0.lock().unwrap().entered.push(span.clone());
+    }
+
+    fn exit(&self, _span: &Id) {
+        self.0.lock().unwrap().entered.pop();
+    }
+
+    fn current_span(&self) -> Current {
+        let spans = self.0.lock().unwrap();
+        match spans.entered.last() {
+            Some(id) => Current::new(id.clone(), spans.opened[id_index(id)].0),
+            None => Current::none(),
+        }
+    }
+}
+
+#[test]
+fn test_causal_tree() {
+    let recorder = Recorder::default();
+    tracing::subscriber::set_global_default(recorder.clone()).unwrap();
+
+    use Action::*;
+    let mut store = LocalStore::<State>::default();
+
+    tracing::debug_span!("test").in_scope(|| {
+        store.send(External('1')); // sends `A` to `D` through `Effects::action`
+        store.send(External('F')); // sends `f` from a task
+    });
+
+    store.run_until_stalled();
+    store.into_inner();
+
+    assert_eq!(
+        recorder.paths(),
+        [
+            "test",
+            "test > reduce",
+            "test > reduce",
+            "test > reduce > reduce",
+            "test > reduce > reduce",
+            "test > reduce > reduce",
+            "test > reduce > reduce",
+            "test > reduce > task",
+            "test > reduce > task > reduce",
+        ]
+    );
+}